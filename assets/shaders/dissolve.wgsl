@@ -0,0 +1,28 @@
+#import bevy_tickles::particle_types
+
+struct DissolveMaterial {
+    edge_color: vec4<f32>;
+    edge_width: f32;
+};
+[[group(3), binding(0)]]
+var<uniform> material: DissolveMaterial;
+
+fn noise(uv: vec2<f32>) -> f32 {
+    return fract(sin(dot(floor(uv * 24.0), vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+[[stage(fragment)]]
+fn fragment(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let color = textureSample(sprite_texture, sprite_sampler, in.uv);
+    // The particle alpha drives the dissolution instead of the transparency
+    let threshold = 1.0 - in.color.a;
+    let value = noise(in.uv);
+    if (value < threshold) {
+        discard;
+    }
+    var rgb = in.color.rgb * color.rgb;
+    if (value < threshold + material.edge_width) {
+        rgb = material.edge_color.rgb;
+    }
+    return vec4<f32>(rgb, color.a);
+}
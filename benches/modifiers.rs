@@ -0,0 +1,91 @@
+use bevy::ecs::schedule::{Stage, SystemStage};
+use bevy::prelude::{GlobalTransform, Vec3, World};
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use bevy_tickles::prelude::modifiers::{
+    AngularVelocityOverTime, ColorOverLifeTime, MaxParticleSpeed, ParticleGravity, PerlinNoise,
+    RotationOverVelocity, SizeOverTime, SpeedOverTime,
+};
+use bevy_tickles::prelude::{Particle, ParticleRng, ParticleSimulation, ParticleSystem};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const DELTA_TIME: f32 = 1.0 / 60.0;
+
+#[allow(clippy::field_reassign_with_default)]
+fn particle_system(count: usize) -> ParticleSystem {
+    let mut particle_system = ParticleSystem::default();
+    let transform = GlobalTransform::identity();
+    for i in 0..count {
+        let mut particle = Particle::default();
+        particle.translation = Vec3::splat(i as f32 * 0.01);
+        particle.velocity = Vec3::Y;
+        particle.lifetime = 5.0;
+        particle.start_lifetime = 5.0;
+        particle_system.push(particle, &transform);
+    }
+    particle_system
+}
+
+/// World with a single particle system of `count` particles and every benchmarked modifier
+fn world(count: usize) -> World {
+    let mut world = World::new();
+    world.insert_resource(ComputeTaskPool(TaskPool::new()));
+    world
+        .spawn()
+        .insert(particle_system(count))
+        .insert(ParticleRng::from_seed(0))
+        .insert(ParticleGravity::default())
+        .insert(SpeedOverTime::default())
+        .insert(MaxParticleSpeed::default())
+        .insert(AngularVelocityOverTime::default())
+        .insert(SizeOverTime::default())
+        .insert(RotationOverVelocity::default())
+        .insert(ColorOverLifeTime::default())
+        .insert(PerlinNoise::default());
+    world
+}
+
+/// Simulation running only the modifier systems
+fn modifier_simulation() -> ParticleSimulation {
+    let mut simulation = ParticleSimulation::default();
+    simulation.stage = SystemStage::single_threaded();
+    simulation
+}
+
+fn run(world: &mut World, simulation: &mut ParticleSimulation) {
+    for mut particle_system in world.query::<&mut ParticleSystem>().iter_mut(world) {
+        particle_system.begin_step(DELTA_TIME);
+    }
+    simulation.stage.run(world);
+}
+
+fn modifier_passes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("modifiers");
+    for count in [1_000, 10_000, 100_000] {
+        // One system per modifier component, each iterating over every particle
+        let mut separate = modifier_simulation();
+        separate
+            .add_modifier::<ParticleGravity>()
+            .add_modifier::<SpeedOverTime>()
+            .add_modifier::<MaxParticleSpeed>()
+            .add_modifier::<AngularVelocityOverTime>()
+            .add_modifier::<SizeOverTime>()
+            .add_modifier::<RotationOverVelocity>()
+            .add_modifier::<ColorOverLifeTime>()
+            .add_rng_modifier::<PerlinNoise>();
+        let mut world_separate = world(count);
+        group.bench_with_input(BenchmarkId::new("separate", count), &count, |b, _| {
+            b.iter(|| run(&mut world_separate, &mut separate));
+        });
+        // The same modifier components, applied in a single pass
+        let mut fused = modifier_simulation();
+        fused.add_modifier_stack();
+        let mut world_fused = world(count);
+        group.bench_with_input(BenchmarkId::new("fused", count), &count, |b, _| {
+            b.iter(|| run(&mut world_fused, &mut fused));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, modifier_passes);
+criterion_main!(benches);
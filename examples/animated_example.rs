@@ -47,14 +47,14 @@ fn spawn_particle_system(
             }
             .into(),
             particle_params: ParticleParams {
-                start_size: RangeOrFixed::Range { min: 1.0, max: 4.0 },
+                start_size: RangeOrFixed::Range { min: 1.0, max: 4.0 }.into(),
                 start_speed: 0.0.into(),
                 start_lifetime: 1.0.into(),
                 start_color: ColorGradient::rainbow().into(),
                 ..Default::default()
             },
             particle_emitter: ParticleEmitter {
-                rate: 300.0,
+                rate: 300.0.into(),
                 shape: EmitterShape {
                     shape: Shape::Sphere(Sphere {
                         radius: 10.0,
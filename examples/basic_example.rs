@@ -23,7 +23,7 @@ fn spawn_particle_system(mut commands: Commands, asset_server: Res<AssetServer>)
     commands
         .spawn_bundle(ParticleSystemBundle {
             transform: Transform::from_xyz(0., 5., 0.),
-            material: ParticleMaterial::Image(asset_server.load("wrench.png")),
+            material: ParticleMaterial::image(asset_server.load("wrench.png")),
             particle_params: ParticleParams {
                 rotation: RotationMode::FreeRotation {
                     start_rotation: RangeOrFixed::Range {
@@ -37,7 +37,7 @@ fn spawn_particle_system(mut commands: Commands, asset_server: Res<AssetServer>)
                 ..Default::default()
             },
             particle_emitter: ParticleEmitter {
-                rate: 20.0,
+                rate: 20.0.into(),
                 shape: EmitterShape {
                     shape: Shape::Sphere(Sphere {
                         radius: 0.2,
@@ -56,7 +56,7 @@ fn spawn_particle_system(mut commands: Commands, asset_server: Res<AssetServer>)
                 .add_point(0.5, Color::GREEN)
                 .add_point(1.0, Color::rgba(0.5, 0.5, 1.0, 0.0)),
         ))
-        .insert(SizeOverTime(0.5))
+        .insert(SizeOverTime::from(0.5))
         .insert(ParticleGravity(Vec3::new(0., -1.5, 0.)))
         .insert(AngularVelocityOverTime(1.0))
         .insert(OrbitalVelocityOverLifeTime::default())
@@ -0,0 +1,150 @@
+use bevy::ecs::system::{lifetimeless::SRes, SystemParamItem};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::render_asset::{PrepareAssetError, RenderAsset};
+use bevy::render::render_resource::std140::{AsStd140, Std140};
+use bevy::render::render_resource::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferInitDescriptor, BufferSize,
+    BufferUsages, ShaderStages,
+};
+use bevy::render::renderer::RenderDevice;
+use bevy_inspector_egui::WorldInspectorPlugin;
+use bevy_tickles::prelude::modifiers::*;
+use bevy_tickles::prelude::shapes::Sphere;
+use bevy_tickles::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ParticlesPlugin)
+        .add_plugin(CustomParticleMaterialPlugin::<DissolveMaterial>::default())
+        .add_plugin(WorldInspectorPlugin::default())
+        .add_startup_system(spawn_particle_system)
+        .run();
+}
+
+/// Dissolves the particles as they fade out, with an emissive edge
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "4c3a0d1e-8f2b-4b7a-9a1d-5e2f6c7b8a90"]
+pub struct DissolveMaterial {
+    pub edge_color: Color,
+    pub edge_width: f32,
+}
+
+#[derive(Clone, AsStd140)]
+struct DissolveUniform {
+    edge_color: Vec4,
+    edge_width: f32,
+}
+
+pub struct GpuDissolveMaterial {
+    _buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl RenderAsset for DissolveMaterial {
+    type ExtractedAsset = Self;
+    type PreparedAsset = GpuDissolveMaterial;
+    type Param = (
+        SRes<RenderDevice>,
+        SRes<CustomParticlePipeline<DissolveMaterial>>,
+    );
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        material: Self::ExtractedAsset,
+        (render_device, pipeline): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        let uniform = DissolveUniform {
+            edge_color: material.edge_color.as_linear_rgba_f32().into(),
+            edge_width: material.edge_width,
+        };
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            contents: uniform.as_std140().as_bytes(),
+            label: Some("dissolve_material_buffer"),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+            label: Some("dissolve_material_bind_group"),
+            layout: &pipeline.material_layout,
+        });
+        Ok(GpuDissolveMaterial {
+            _buffer: buffer,
+            bind_group,
+        })
+    }
+}
+
+impl CustomParticleMaterial for DissolveMaterial {
+    fn bind_group(material: &GpuDissolveMaterial) -> &BindGroup {
+        &material.bind_group
+    }
+
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
+        render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: BufferSize::new(DissolveUniform::std140_size_static() as u64),
+                },
+                count: None,
+            }],
+            label: Some("dissolve_material_layout"),
+        })
+    }
+
+    fn fragment_shader(asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(asset_server.load("shaders/dissolve.wgsl"))
+    }
+}
+
+fn spawn_particle_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<DissolveMaterial>>,
+) {
+    commands.spawn_bundle(PerspectiveCameraBundle {
+        transform: Transform::from_xyz(10.0, 10.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..PerspectiveCameraBundle::new_3d()
+    });
+    commands
+        .spawn_bundle(ParticleSystemBundle {
+            material: ParticleMaterial::image(asset_server.load("kenney/smoke_04.png")),
+            particle_params: ParticleParams {
+                start_lifetime: (2.0..=3.0).into(),
+                start_size: RangeOrFixed::from(1.0..=2.0).into(),
+                start_speed: (0.5..=1.5).into(),
+                ..Default::default()
+            },
+            particle_emitter: ParticleEmitter {
+                rate: 10.0.into(),
+                shape: EmitterShape {
+                    shape: Shape::Sphere(Sphere {
+                        radius: 1.0,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(materials.add(DissolveMaterial {
+            edge_color: Color::ORANGE_RED,
+            edge_width: 0.1,
+        }))
+        // The particle alpha drives the dissolution
+        .insert(AlphaOverLifetime(Curve::linear(1.0, 0.0)))
+        .insert(Name::new("Dissolving smoke"));
+}
@@ -0,0 +1,60 @@
+use bevy::asset::AssetServerSettings;
+use bevy::prelude::*;
+use bevy_inspector_egui::WorldInspectorPlugin;
+use bevy_tickles::prelude::*;
+
+fn main() {
+    App::new()
+        // Enables hot reloading: edit `assets/effects/fire.particle.ron` while the example runs
+        .insert_resource(AssetServerSettings {
+            watch_for_changes: true,
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ParticlesPlugin)
+        .add_plugin(WorldInspectorPlugin::default())
+        .add_startup_system(spawn_particle_effect)
+        .add_startup_system(spawn_cubes)
+        .run();
+}
+
+fn spawn_particle_effect(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(PerspectiveCameraBundle {
+        transform: Transform::from_xyz(10.0, 10.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..PerspectiveCameraBundle::new_3d()
+    });
+    commands.spawn_bundle(DirectionalLightBundle::default());
+    commands
+        .spawn_bundle(ParticleEffectBundle {
+            effect: asset_server.load("effects/fire.particle.ron"),
+            transform: Transform::from_xyz(0., 0.5, 0.),
+            ..Default::default()
+        })
+        .insert(Name::new("Fire Effect"));
+}
+
+fn spawn_cubes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(shape::Cube::new(1.).into());
+    commands.spawn_bundle(PbrBundle {
+        mesh: mesh.clone(),
+        material: materials.add(Color::WHITE.into()),
+        transform: Transform::from_xyz(0., 0., 0.),
+        ..Default::default()
+    });
+    commands.spawn_bundle(PbrBundle {
+        mesh: mesh.clone(),
+        material: materials.add(Color::RED.into()),
+        transform: Transform::from_xyz(-5., 0., 0.),
+        ..Default::default()
+    });
+    commands.spawn_bundle(PbrBundle {
+        mesh,
+        material: materials.add(Color::BLUE.into()),
+        transform: Transform::from_xyz(5., 0., 0.),
+        ..Default::default()
+    });
+}
@@ -60,7 +60,7 @@ fn spawn_particle_system(
                 ..Default::default()
             },
             particle_emitter: ParticleEmitter {
-                rate: 0.0,
+                rate: 0.0.into(),
                 shape: EmitterShape {
                     shape: Shape::Sphere(Sphere {
                         radius: 0.0,
@@ -81,7 +81,7 @@ fn spawn_particle_system(
     commands
         .spawn_bundle(ParticleSystemBundle {
             transform: Transform::from_xyz(0., 0., 0.),
-            material: ParticleMaterial::Image(smoke_texture),
+            material: ParticleMaterial::image(smoke_texture),
             particle_params: ParticleParams {
                 start_size: 0.1.into(),
                 start_speed: 6.0.into(),
@@ -93,7 +93,7 @@ fn spawn_particle_system(
                 ..Default::default()
             },
             particle_emitter: ParticleEmitter {
-                rate: 0.0,
+                rate: 0.0.into(),
                 shape: EmitterShape {
                     shape: Shape::Circle(Circle { radius: 1.0 }),
                     thickness: 0.0,
@@ -126,7 +126,7 @@ fn spawn_particle_system(
                 .add_point(0.5, Color::GRAY)
                 .add_point(1.0, Color::NONE),
         ))
-        .insert(SizeOverTime(1.5))
+        .insert(SizeOverTime::from(1.5))
         .insert(RotationOverVelocity {
             value: 1.0,
             abs: false,
@@ -135,7 +135,7 @@ fn spawn_particle_system(
     commands
         .spawn_bundle(ParticleSystemBundle {
             transform: Transform::from_xyz(0., 0., 0.),
-            material: ParticleMaterial::Image(scorch_texture),
+            material: ParticleMaterial::image(scorch_texture),
             particle_params: ParticleParams {
                 start_size: 1.0.into(),
                 start_speed: (5.0..=6.0).into(),
@@ -144,7 +144,7 @@ fn spawn_particle_system(
                 ..Default::default()
             },
             particle_emitter: ParticleEmitter {
-                rate: 0.0,
+                rate: 0.0.into(),
                 shape: EmitterShape {
                     shape: Shape::Sphere(Sphere {
                         radius: 2.0,
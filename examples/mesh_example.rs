@@ -27,7 +27,7 @@ fn init(mut commands: Commands) {
 fn spawn_particle_system(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands
         .spawn_bundle(ParticleSystemBundle {
-            material: ParticleMaterial::Image(asset_server.load("wrench.png")),
+            material: ParticleMaterial::image(asset_server.load("wrench.png")),
             particle_params: ParticleParams {
                 start_size: 1.0.into(),
                 start_speed: 0.0.into(),
@@ -35,7 +35,7 @@ fn spawn_particle_system(mut commands: Commands, asset_server: Res<AssetServer>)
                 ..Default::default()
             },
             particle_emitter: ParticleEmitter {
-                rate: 30.0,
+                rate: 30.0.into(),
                 shape: EmitterShape {
                     shape: Shape::ConvexMesh(ConvexMesh {
                         mesh: Mesh::from(Torus {
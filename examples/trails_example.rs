@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::WorldInspectorPlugin;
+use bevy_tickles::prelude::modifiers::*;
+use bevy_tickles::prelude::shapes::{Circle, Sphere};
+use bevy_tickles::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ParticlesPlugin)
+        .add_plugin(WorldInspectorPlugin::default())
+        .add_startup_system(init)
+        .add_startup_system(spawn_sparks)
+        .add_startup_system(spawn_ribbon)
+        .run();
+}
+
+fn init(mut commands: Commands) {
+    commands.spawn_bundle(PerspectiveCameraBundle {
+        transform: Transform::from_xyz(10.0, 10.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..PerspectiveCameraBundle::new_3d()
+    });
+}
+
+fn spawn_sparks(mut commands: Commands) {
+    commands
+        .spawn_bundle(ParticleSystemBundle {
+            material: ParticleMaterial::default().with_blend_mode(ParticleBlendMode::Additive),
+            particle_params: ParticleParams {
+                start_lifetime: (1.0..=2.0).into(),
+                start_size: 0.1.into(),
+                start_speed: (4.0..=8.0).into(),
+                ..Default::default()
+            },
+            particle_emitter: ParticleEmitter {
+                rate: 15.0.into(),
+                shape: EmitterShape {
+                    shape: Shape::Sphere(Sphere {
+                        radius: 0.2,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(ParticleTrails {
+            mode: TrailMode::PerParticle {
+                lifetime: 0.4,
+                minimum_vertex_distance: 0.1,
+            },
+            color_over_trail: (Color::WHITE, Color::rgba(1.0, 0.3, 0.0, 0.0)).into(),
+            render_particles: false,
+            ..Default::default()
+        })
+        .insert(ParticleGravity(Vec3::new(0., -9.81, 0.)))
+        .insert(Name::new("Sparks"));
+}
+
+fn spawn_ribbon(mut commands: Commands) {
+    commands
+        .spawn_bundle(ParticleSystemBundle {
+            transform: Transform::from_xyz(0., 3., 0.),
+            particle_params: ParticleParams {
+                start_lifetime: 3.0.into(),
+                start_size: 0.3.into(),
+                start_speed: 0.0.into(),
+                ..Default::default()
+            },
+            particle_emitter: ParticleEmitter {
+                rate: 10.0.into(),
+                shape: EmitterShape {
+                    shape: Shape::Circle(Circle { radius: 4.0 }),
+                    thickness: 0.0,
+                    mode: EmissionMode::Spread(EmissionSpread {
+                        spreads: [
+                            AxisSpread::none(),
+                            AxisSpread {
+                                amount: 1.0 / 30.0,
+                                loop_mode: Default::default(),
+                                uniform: true,
+                            },
+                            AxisSpread::none(),
+                        ],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(ParticleTrails {
+            mode: TrailMode::Ribbon,
+            width_over_trail: 1.0.into(),
+            color_over_trail: ColorGradient::rainbow(),
+            render_particles: false,
+        })
+        .insert(Name::new("Ribbon"));
+}
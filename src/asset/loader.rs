@@ -0,0 +1,90 @@
+use crate::asset::ParticleEffect;
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use ron::extensions::Extensions;
+
+/// Asset loader for [`ParticleEffect`] files with the `.particle.ron` extension
+///
+/// The `unwrap_newtypes` and `implicit_some` RON extensions are enabled by default
+#[derive(Debug, Default)]
+pub struct ParticleEffectLoader;
+
+/// RON options of the [`ParticleEffect`] files
+fn ron_options() -> ron::Options {
+    ron::Options::default()
+        .with_default_extension(Extensions::UNWRAP_NEWTYPES | Extensions::IMPLICIT_SOME)
+}
+
+impl AssetLoader for ParticleEffectLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let mut effect: ParticleEffect = ron_options().from_bytes(bytes)?;
+            let mut dependencies = effect.material.load(load_context);
+            dependencies.extend(effect.render_mode.load(load_context));
+            load_context
+                .set_default_asset(LoadedAsset::new(effect).with_dependencies(dependencies));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["particle.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::ParticleEffectRenderMode;
+    use crate::ParticleRenderMode;
+    use std::fs;
+
+    /// Deserializes `source` and serializes it back
+    fn round_trip(source: &str) -> (ParticleEffect, String) {
+        let effect: ParticleEffect = ron_options().from_str(source).unwrap();
+        let serialized = ron_options().to_string(&effect).unwrap();
+        (effect, serialized)
+    }
+
+    #[test]
+    fn effect_assets_round_trip() {
+        let mut count = 0;
+        for entry in fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/effects")).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.to_string_lossy().ends_with(".particle.ron") {
+                continue;
+            }
+            let source = fs::read_to_string(&path).unwrap();
+            let (_, serialized) = round_trip(&source);
+            // The serialized effect deserializes to the same effect
+            let (_, serialized_again) = round_trip(&serialized);
+            assert_eq!(serialized, serialized_again, "{}", path.display());
+            count += 1;
+        }
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn mesh_render_mode_round_trip() {
+        let path = "models/rock.gltf#Mesh0/Primitive0";
+        let source =
+            format!("(material: Image(path: \"smoke.png\"), render_mode: Mesh(path: \"{path}\"))");
+        let (effect, serialized) = round_trip(&source);
+        assert!(matches!(
+            &effect.render_mode,
+            ParticleEffectRenderMode::Mesh { path: p, .. } if p == path
+        ));
+        assert!(matches!(
+            effect.render_mode.render_mode(),
+            ParticleRenderMode::Mesh(_)
+        ));
+        let (effect, _) = round_trip(&serialized);
+        assert!(matches!(
+            &effect.render_mode,
+            ParticleEffectRenderMode::Mesh { path: p, .. } if p == path
+        ));
+    }
+}
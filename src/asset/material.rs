@@ -0,0 +1,120 @@
+use crate::{
+    ParticleBlendMode, ParticleMaterial, ParticleTexture, ParticleTextureSheet, TextureSheetMode,
+};
+use bevy::asset::{AssetPath, LoadContext, LoadedAsset};
+use bevy::prelude::{Handle, Image, TextureAtlas, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// Serializable description of a [`ParticleMaterial`], referencing images by their asset path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ParticleEffectMaterial {
+    /// Single image material
+    Image {
+        /// The image asset path
+        path: String,
+        /// The particle blend mode
+        #[serde(default)]
+        blend_mode: ParticleBlendMode,
+        /// Alpha threshold of the [`ParticleBlendMode::Opaque`] mode
+        #[serde(default = "default_alpha_cutoff")]
+        alpha_cutoff: f32,
+        #[doc(hidden)]
+        #[serde(skip)]
+        handle: Handle<Image>,
+    },
+    /// Texture sheet material, the texture atlas is built as a grid over the image
+    TextureSheet {
+        /// The texture sheet image asset path
+        path: String,
+        /// The size of a single cell
+        tile_size: Vec2,
+        /// The amount of columns in the grid
+        columns: usize,
+        /// The amount of rows in the grid
+        rows: usize,
+        /// Texture sheet resolve mode
+        #[serde(default)]
+        mode: TextureSheetMode,
+        /// The particle blend mode
+        #[serde(default)]
+        blend_mode: ParticleBlendMode,
+        /// Alpha threshold of the [`ParticleBlendMode::Opaque`] mode
+        #[serde(default = "default_alpha_cutoff")]
+        alpha_cutoff: f32,
+        #[doc(hidden)]
+        #[serde(skip)]
+        texture_atlas: Handle<TextureAtlas>,
+    },
+}
+
+const fn default_alpha_cutoff() -> f32 {
+    0.5
+}
+
+impl ParticleEffectMaterial {
+    /// Retrieves the [`ParticleMaterial`] component matching the description.
+    ///
+    /// Note: The handles are only set once the effect is loaded by the
+    /// [`ParticleEffectLoader`](crate::prelude::ParticleEffectLoader)
+    #[must_use]
+    pub fn material(&self) -> ParticleMaterial {
+        match self {
+            Self::Image {
+                handle,
+                blend_mode,
+                alpha_cutoff,
+                ..
+            } => ParticleMaterial {
+                texture: ParticleTexture::Image(handle.clone()),
+                blend_mode: *blend_mode,
+                alpha_cutoff: *alpha_cutoff,
+            },
+            Self::TextureSheet {
+                mode,
+                texture_atlas,
+                blend_mode,
+                alpha_cutoff,
+                ..
+            } => ParticleMaterial {
+                texture: ParticleTexture::TextureSheet(ParticleTextureSheet {
+                    texture_atlas: texture_atlas.clone(),
+                    mode: mode.clone(),
+                }),
+                blend_mode: *blend_mode,
+                alpha_cutoff: *alpha_cutoff,
+            },
+        }
+    }
+
+    /// Retrieves the handles from `load_context` and returns the asset dependencies
+    pub(crate) fn load(&mut self, load_context: &mut LoadContext) -> Vec<AssetPath<'static>> {
+        match self {
+            Self::Image { path, handle, .. } => {
+                let asset_path = AssetPath::from(path.as_str()).to_owned();
+                *handle = load_context.get_handle(asset_path.clone());
+                vec![asset_path]
+            }
+            Self::TextureSheet {
+                path,
+                tile_size,
+                columns,
+                rows,
+                texture_atlas,
+                ..
+            } => {
+                let asset_path = AssetPath::from(path.as_str()).to_owned();
+                let atlas = TextureAtlas::from_grid(
+                    load_context.get_handle(asset_path.clone()),
+                    *tile_size,
+                    *columns,
+                    *rows,
+                );
+                *texture_atlas = load_context.set_labeled_asset(
+                    "texture_atlas",
+                    LoadedAsset::new(atlas).with_dependency(asset_path.clone()),
+                );
+                vec![asset_path]
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+mod loader;
+mod material;
+mod modifier;
+mod render_mode;
+
+use crate::{
+    ParticleEmitter, ParticleMaterial, ParticleParams, ParticlePlayback, ParticleRenderMode,
+    ParticleRng, ParticleSystem, ParticleTrails, StopAction,
+};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::{GlobalTransform, Mut};
+use bevy::reflect::TypeUuid;
+use bevy::render::primitives::Aabb;
+use serde::{Deserialize, Serialize};
+
+pub use {
+    loader::ParticleEffectLoader,
+    material::ParticleEffectMaterial,
+    modifier::{ParticleEffectModifier, ParticleEffectModifiers},
+    render_mode::ParticleEffectRenderMode,
+};
+
+/// Particle effect asset, describing a complete particle system with its modifiers.
+///
+/// The asset is loaded from `.particle.ron` files through [`ParticleEffectLoader`] and can be
+/// spawned using a [`ParticleEffectBundle`](crate::prelude::ParticleEffectBundle)
+#[derive(Debug, Clone, Serialize, Deserialize, TypeUuid)]
+#[uuid = "b38e1b34-251d-44d1-a968-310a2fd1382d"]
+pub struct ParticleEffect {
+    /// If enabled, the particles won't be stuck to the particle system entity
+    #[serde(default)]
+    pub world_space: bool,
+    /// The particle emitter
+    #[serde(default)]
+    pub emitter: ParticleEmitter,
+    /// The particle params
+    #[serde(default)]
+    pub params: ParticleParams,
+    /// The visual for the particles
+    pub material: ParticleEffectMaterial,
+    /// The particle render and alignment mode
+    #[serde(default)]
+    pub render_mode: ParticleEffectRenderMode,
+    /// The modifiers to insert on the particle system
+    #[serde(default)]
+    pub modifiers: Vec<ParticleEffectModifier>,
+    /// The particle trails, if any
+    #[serde(default)]
+    pub trails: Option<ParticleTrails>,
+    /// Action applied once the particle system is finished
+    #[serde(default)]
+    pub stop_action: StopAction,
+    /// Seed of the particle system randomizer, for reproducible particle systems
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl ParticleEffect {
+    /// Inserts the particle system components and modifiers described by the effect on the
+    /// given entity
+    pub fn insert(&self, commands: &mut EntityCommands) {
+        commands.insert_bundle((
+            ParticleSystem {
+                world_space: self.world_space,
+                ..Default::default()
+            },
+            self.emitter.clone(),
+            self.params.clone(),
+            self.seed
+                .map_or_else(ParticleRng::default, ParticleRng::from_seed),
+            self.render_mode.render_mode(),
+            self.material.material(),
+            Aabb::default(),
+            self.stop_action,
+            ParticlePlayback::default(),
+        ));
+        if let Some(trails) = &self.trails {
+            commands.insert(trails.clone());
+        }
+        self.insert_modifiers(commands);
+    }
+
+    /// Inserts the effect modifiers on the given entity, keeping track of them
+    fn insert_modifiers(&self, commands: &mut EntityCommands) {
+        for modifier in &self.modifiers {
+            modifier.insert(commands);
+        }
+        commands.insert(ParticleEffectModifiers(self.modifiers.clone()));
+    }
+
+    /// Re-applies the effect on an already spawned particle system, keeping its live particles
+    /// and the emission timing.
+    ///
+    /// Note: The modifiers inserted by the previous version of the effect, listed in
+    /// `inserted_modifiers`, are removed from the entity. The modifiers added separately are kept
+    #[allow(clippy::too_many_arguments)]
+    pub fn reapply(
+        &self,
+        commands: &mut EntityCommands,
+        inserted_modifiers: Option<&ParticleEffectModifiers>,
+        transform: &GlobalTransform,
+        mut particle_system: Mut<ParticleSystem>,
+        mut emitter: Mut<ParticleEmitter>,
+        mut params: Mut<ParticleParams>,
+        mut render_mode: Mut<ParticleRenderMode>,
+        mut material: Mut<ParticleMaterial>,
+    ) {
+        particle_system.set_world_space(self.world_space, transform);
+        *emitter = ParticleEmitter {
+            current_delta_time: emitter.current_delta_time,
+            pending_emission: emitter.pending_emission,
+            ..self.emitter.clone()
+        };
+        *params = self.params.clone();
+        *render_mode = self.render_mode.render_mode();
+        *material = self.material.material();
+        commands.insert(self.stop_action);
+        match &self.trails {
+            Some(trails) => commands.insert(trails.clone()),
+            None => commands.remove::<ParticleTrails>(),
+        };
+        for modifier in inserted_modifiers.iter().flat_map(|m| m.0.iter()) {
+            modifier.remove(commands);
+        }
+        self.insert_modifiers(commands);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::modifiers::{
+    AlphaOverLifetime, AngularVelocityOverTime, AngularVelocityOverTime3d, ColorOverLifeTime,
+    ColorOverSpeed, LinearVelocityOverLifeTime, MaxParticleCount, MaxParticleSize,
+    MaxParticleSpeed, OrbitalVelocityOverLifeTime, ParticleCollision, ParticleGravity,
+    ParticleModifiers, PerlinNoise, RotationOverLifetime, RotationOverTime, RotationOverTime3d,
+    RotationOverVelocity, SizeOverLifetime, SizeOverSpeed, SizeOverTime, SpeedOverLifetime,
+    SpeedOverTime, VelocityOverTime,
+};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/// Serializable wrapper for every built-in particle modifier component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum ParticleEffectModifier {
+    /// [`MaxParticleCount`] modifier
+    MaxParticleCount(MaxParticleCount),
+    /// [`MaxParticleSize`] modifier
+    MaxParticleSize(MaxParticleSize),
+    /// [`MaxParticleSpeed`] modifier
+    MaxParticleSpeed(MaxParticleSpeed),
+    /// [`ParticleGravity`] modifier
+    ParticleGravity(ParticleGravity),
+    /// [`SpeedOverTime`] modifier
+    SpeedOverTime(SpeedOverTime),
+    /// [`SpeedOverLifetime`] modifier
+    SpeedOverLifetime(SpeedOverLifetime),
+    /// [`VelocityOverTime`] modifier
+    VelocityOverTime(VelocityOverTime),
+    /// [`AngularVelocityOverTime`] modifier
+    AngularVelocityOverTime(AngularVelocityOverTime),
+    /// [`AngularVelocityOverTime3d`] modifier
+    AngularVelocityOverTime3d(AngularVelocityOverTime3d),
+    /// [`OrbitalVelocityOverLifeTime`] modifier
+    OrbitalVelocityOverLifeTime(OrbitalVelocityOverLifeTime),
+    /// [`LinearVelocityOverLifeTime`] modifier
+    LinearVelocityOverLifeTime(LinearVelocityOverLifeTime),
+    /// [`SizeOverTime`] modifier
+    SizeOverTime(SizeOverTime),
+    /// [`SizeOverSpeed`] modifier
+    SizeOverSpeed(SizeOverSpeed),
+    /// [`SizeOverLifetime`] modifier
+    SizeOverLifetime(SizeOverLifetime),
+    /// [`RotationOverVelocity`] modifier
+    RotationOverVelocity(RotationOverVelocity),
+    /// [`RotationOverTime`] modifier
+    RotationOverTime(RotationOverTime),
+    /// [`RotationOverTime3d`] modifier
+    RotationOverTime3d(RotationOverTime3d),
+    /// [`RotationOverLifetime`] modifier
+    RotationOverLifetime(RotationOverLifetime),
+    /// [`ColorOverLifeTime`] modifier
+    ColorOverLifeTime(ColorOverLifeTime),
+    /// [`ColorOverSpeed`] modifier
+    ColorOverSpeed(ColorOverSpeed),
+    /// [`AlphaOverLifetime`] modifier
+    AlphaOverLifetime(AlphaOverLifetime),
+    /// [`PerlinNoise`] modifier
+    PerlinNoise(PerlinNoise),
+    /// [`ParticleCollision`] modifier
+    ParticleCollision(ParticleCollision),
+    /// [`ParticleModifiers`] stack
+    ParticleModifiers(ParticleModifiers),
+}
+
+/// Modifiers inserted on a particle system by its [`ParticleEffect`](super::ParticleEffect).
+///
+/// Only these modifiers are removed when the effect is re-applied, the modifiers added separately
+/// to the particle system are kept
+#[derive(Debug, Clone, Default, Component)]
+pub struct ParticleEffectModifiers(pub(crate) Vec<ParticleEffectModifier>);
+
+impl ParticleEffectModifier {
+    /// Inserts the modifier component on the given entity
+    pub fn insert(&self, commands: &mut EntityCommands) {
+        match self.clone() {
+            Self::MaxParticleCount(m) => commands.insert(m),
+            Self::MaxParticleSize(m) => commands.insert(m),
+            Self::MaxParticleSpeed(m) => commands.insert(m),
+            Self::ParticleGravity(m) => commands.insert(m),
+            Self::SpeedOverTime(m) => commands.insert(m),
+            Self::SpeedOverLifetime(m) => commands.insert(m),
+            Self::VelocityOverTime(m) => commands.insert(m),
+            Self::AngularVelocityOverTime(m) => commands.insert(m),
+            Self::AngularVelocityOverTime3d(m) => commands.insert(m),
+            Self::OrbitalVelocityOverLifeTime(m) => commands.insert(m),
+            Self::LinearVelocityOverLifeTime(m) => commands.insert(m),
+            Self::SizeOverTime(m) => commands.insert(m),
+            Self::SizeOverSpeed(m) => commands.insert(m),
+            Self::SizeOverLifetime(m) => commands.insert(m),
+            Self::RotationOverVelocity(m) => commands.insert(m),
+            Self::RotationOverTime(m) => commands.insert(m),
+            Self::RotationOverTime3d(m) => commands.insert(m),
+            Self::RotationOverLifetime(m) => commands.insert(m),
+            Self::ColorOverLifeTime(m) => commands.insert(m),
+            Self::ColorOverSpeed(m) => commands.insert(m),
+            Self::AlphaOverLifetime(m) => commands.insert(m),
+            Self::PerlinNoise(m) => commands.insert(m),
+            Self::ParticleCollision(m) => commands.insert(m),
+            Self::ParticleModifiers(m) => commands.insert(m),
+        };
+    }
+
+    /// Removes the modifier component from the given entity
+    pub fn remove(&self, commands: &mut EntityCommands) {
+        match self {
+            Self::MaxParticleCount(_) => commands.remove::<MaxParticleCount>(),
+            Self::MaxParticleSize(_) => commands.remove::<MaxParticleSize>(),
+            Self::MaxParticleSpeed(_) => commands.remove::<MaxParticleSpeed>(),
+            Self::ParticleGravity(_) => commands.remove::<ParticleGravity>(),
+            Self::SpeedOverTime(_) => commands.remove::<SpeedOverTime>(),
+            Self::SpeedOverLifetime(_) => commands.remove::<SpeedOverLifetime>(),
+            Self::VelocityOverTime(_) => commands.remove::<VelocityOverTime>(),
+            Self::AngularVelocityOverTime(_) => commands.remove::<AngularVelocityOverTime>(),
+            Self::AngularVelocityOverTime3d(_) => commands.remove::<AngularVelocityOverTime3d>(),
+            Self::OrbitalVelocityOverLifeTime(_) => {
+                commands.remove::<OrbitalVelocityOverLifeTime>()
+            }
+            Self::LinearVelocityOverLifeTime(_) => commands.remove::<LinearVelocityOverLifeTime>(),
+            Self::SizeOverTime(_) => commands.remove::<SizeOverTime>(),
+            Self::SizeOverSpeed(_) => commands.remove::<SizeOverSpeed>(),
+            Self::SizeOverLifetime(_) => commands.remove::<SizeOverLifetime>(),
+            Self::RotationOverVelocity(_) => commands.remove::<RotationOverVelocity>(),
+            Self::RotationOverTime(_) => commands.remove::<RotationOverTime>(),
+            Self::RotationOverTime3d(_) => commands.remove::<RotationOverTime3d>(),
+            Self::RotationOverLifetime(_) => commands.remove::<RotationOverLifetime>(),
+            Self::ColorOverLifeTime(_) => commands.remove::<ColorOverLifeTime>(),
+            Self::ColorOverSpeed(_) => commands.remove::<ColorOverSpeed>(),
+            Self::AlphaOverLifetime(_) => commands.remove::<AlphaOverLifetime>(),
+            Self::PerlinNoise(_) => commands.remove::<PerlinNoise>(),
+            Self::ParticleCollision(_) => commands.remove::<ParticleCollision>(),
+            Self::ParticleModifiers(_) => commands.remove::<ParticleModifiers>(),
+        };
+    }
+}
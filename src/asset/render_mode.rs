@@ -0,0 +1,82 @@
+use crate::{BillBoardAlignment, ParticleRenderMode};
+use bevy::asset::{AssetPath, LoadContext};
+use bevy::prelude::{Handle, Mesh};
+use serde::{Deserialize, Serialize};
+
+/// Serializable description of a [`ParticleRenderMode`], referencing meshes by their asset path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ParticleEffectRenderMode {
+    /// [`ParticleRenderMode::BillBoard`] mode
+    BillBoard {
+        /// Billboard alignment
+        alignment: BillBoardAlignment,
+    },
+    /// [`ParticleRenderMode::VerticalBillboard`] mode
+    VerticalBillboard,
+    /// [`ParticleRenderMode::HorizontalBillBoard`] mode
+    HorizontalBillBoard,
+    /// [`ParticleRenderMode::StretchedBillboard`] mode
+    StretchedBillboard {
+        /// Length added to the particle per unit of speed
+        speed_scale: f32,
+        /// Multiplier of the particle length
+        length_scale: f32,
+        /// Amount of the Camera velocity subtracted from the particles velocity
+        camera_velocity_scale: f32,
+    },
+    /// [`ParticleRenderMode::Mesh`] mode
+    Mesh {
+        /// The mesh asset path, like `models/rock.gltf#Mesh0/Primitive0`
+        path: String,
+        #[doc(hidden)]
+        #[serde(skip)]
+        handle: Handle<Mesh>,
+    },
+}
+
+impl Default for ParticleEffectRenderMode {
+    fn default() -> Self {
+        Self::BillBoard {
+            alignment: BillBoardAlignment::default(),
+        }
+    }
+}
+
+impl ParticleEffectRenderMode {
+    /// Retrieves the [`ParticleRenderMode`] component matching the description.
+    ///
+    /// Note: The mesh handle is only set once the effect is loaded by the
+    /// [`ParticleEffectLoader`](crate::prelude::ParticleEffectLoader)
+    #[must_use]
+    pub fn render_mode(&self) -> ParticleRenderMode {
+        match self {
+            Self::BillBoard { alignment } => ParticleRenderMode::BillBoard {
+                alignment: *alignment,
+            },
+            Self::VerticalBillboard => ParticleRenderMode::VerticalBillboard,
+            Self::HorizontalBillBoard => ParticleRenderMode::HorizontalBillBoard,
+            Self::StretchedBillboard {
+                speed_scale,
+                length_scale,
+                camera_velocity_scale,
+            } => ParticleRenderMode::StretchedBillboard {
+                speed_scale: *speed_scale,
+                length_scale: *length_scale,
+                camera_velocity_scale: *camera_velocity_scale,
+            },
+            Self::Mesh { handle, .. } => ParticleRenderMode::Mesh(handle.clone()),
+        }
+    }
+
+    /// Retrieves the mesh handle from `load_context` and returns the asset dependencies
+    pub(crate) fn load(&mut self, load_context: &mut LoadContext) -> Vec<AssetPath<'static>> {
+        match self {
+            Self::Mesh { path, handle } => {
+                let asset_path = AssetPath::from(path.as_str()).to_owned();
+                *handle = load_context.get_handle(asset_path.clone());
+                vec![asset_path]
+            }
+            _ => vec![],
+        }
+    }
+}
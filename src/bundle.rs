@@ -1,8 +1,8 @@
 use crate::{
-    ParticleEmitter, ParticleMaterial, ParticleParams, ParticleRenderMode, ParticleRng,
-    ParticleSystem,
+    ParticleEffect, ParticleEmitter, ParticleMaterial, ParticleParams, ParticlePlayback,
+    ParticleRenderMode, ParticleRng, ParticleSystem, StopAction,
 };
-use bevy::prelude::{Bundle, ComputedVisibility, GlobalTransform, Transform, Visibility};
+use bevy::prelude::{Bundle, ComputedVisibility, GlobalTransform, Handle, Transform, Visibility};
 use bevy::render::primitives::Aabb;
 
 /// Particle System bundle
@@ -28,6 +28,26 @@ pub struct ParticleSystemBundle {
     pub visibility: Visibility,
     /// Particle System bounding box (computed)
     pub aab: Aabb,
+    /// Action applied once the particle system is finished
+    pub stop_action: StopAction,
+    /// The particle system playback controls
+    pub playback: ParticlePlayback,
+    /// Particle visibility (computed)
+    pub computed_visibility: ComputedVisibility,
+}
+
+/// Particle Effect bundle, the particle system components are inserted once the
+/// [`ParticleEffect`] asset is loaded
+#[derive(Debug, Clone, Default, Bundle)]
+pub struct ParticleEffectBundle {
+    /// The particle effect asset handle
+    pub effect: Handle<ParticleEffect>,
+    /// The entity local translation/rotation/scale
+    pub transform: Transform,
+    /// The entity global translation/rotation/scale (computed)
+    pub global_transform: GlobalTransform,
+    /// Particle visibility
+    pub visibility: Visibility,
     /// Particle visibility (computed)
     pub computed_visibility: ComputedVisibility,
 }
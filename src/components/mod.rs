@@ -4,9 +4,13 @@ pub mod modifiers;
 mod particle_emitter;
 mod particle_material;
 mod particle_params;
+mod particle_playback;
 mod particle_render_mode;
 mod particle_rng;
 mod particle_system;
+mod particle_trails;
+mod stop_action;
+mod sub_emitters;
 
 pub use {
     particle_emitter::{
@@ -17,11 +21,17 @@ pub use {
         Burst, EmitterDuration, ParticleEmitter,
     },
     particle_material::{
-        ParticleMaterial, ParticleTextureSheet, TextureSheetAnimation, TextureSheetLoopingMode,
-        TextureSheetMode,
+        ParticleBlendMode, ParticleMaterial, ParticleTexture, ParticleTextureSheet,
+        TextureSheetAnimation, TextureSheetLoopingMode, TextureSheetMode,
     },
-    particle_params::{ParticleParams, RotationMode},
+    particle_params::{ParticleParams, RotationMode, StartRotation3d, StartSize},
+    particle_playback::{ParticlePlayback, PlaybackState},
     particle_render_mode::{BillBoardAlignment, ParticleRenderMode},
     particle_rng::ParticleRng,
     particle_system::ParticleSystem,
+    particle_trails::{ParticleTrails, TrailMode},
+    stop_action::StopAction,
+    sub_emitters::{SubEmitter, SubEmitterTrigger, SubEmitters},
 };
+
+pub(crate) use particle_trails::TrailStripPoint;
@@ -0,0 +1,170 @@
+use crate::utilities::bvh::{Triangle, TriangleBvh};
+use crate::utilities::mesh_triangles;
+use bevy::asset::HandleId;
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::log;
+use bevy::prelude::{Component, GlobalTransform, Mat4, Mesh, Reflect};
+
+/// Makes a mesh entity collide with particles having a
+/// [`ParticleCollision`](super::ParticleCollision) modifier.
+///
+/// The entity requires a `Handle<Mesh>` and a `GlobalTransform`, like a `PbrBundle`. Its triangles
+/// are stored in world space in a bounding volume hierarchy, which is rebuilt when the mesh or the
+/// transform change, so this is best suited for static meshes.
+///
+/// Only `TriangleList` meshes are supported, and their triangles are two sided.
+#[derive(Debug, Clone, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct MeshCollider {
+    /// The world space triangles hierarchy and the mesh and transform it was built from
+    #[reflect(ignore)]
+    pub(crate) bvh: Option<(HandleId, Mat4, TriangleBvh)>,
+}
+
+impl MeshCollider {
+    /// Checks if the collider hierarchy needs to be rebuilt
+    pub(crate) fn is_outdated(&self, mesh: HandleId, matrix: &Mat4) -> bool {
+        match &self.bvh {
+            Some((id, m, _)) => *id != mesh || m != matrix,
+            None => true,
+        }
+    }
+
+    /// Builds the collider hierarchy from `mesh` with the given `transform`
+    pub(crate) fn build(&mut self, handle: HandleId, mesh: &Mesh, transform: &GlobalTransform) {
+        let matrix = transform.compute_matrix();
+        let triangles = mesh_triangles(mesh)
+            .unwrap_or_else(|| {
+                log::warn!(
+                    "Mesh collider {:?} needs a `TriangleList` mesh with `Float32x3` positions",
+                    handle
+                );
+                Vec::new()
+            })
+            .into_iter()
+            .map(|t| Triangle(t.map(|v| matrix.transform_point3(v))))
+            .collect();
+        self.bvh = Some((handle, matrix, TriangleBvh::new(triangles)));
+    }
+
+    /// Clears the collider hierarchy, forcing a rebuild
+    pub(crate) fn clear(&mut self) {
+        self.bvh = None;
+    }
+
+    /// The world space triangles hierarchy, if built
+    pub(crate) fn bvh(&self) -> Option<&TriangleBvh> {
+        self.bvh.as_ref().map(|(_, _, bvh)| bvh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modifiers::ParticleCollision;
+    use crate::{Particle, ParticleSystem};
+    use bevy::prelude::Vec3;
+    use bevy::render::mesh::{Indices, PrimitiveTopology};
+
+    const DELTA_TIME: f32 = 0.1;
+
+    /// Collider of a two triangles quad, spanning from `-1` to `1` on `x` and `z` at a height of `1`
+    fn quad_collider() -> MeshCollider {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![
+                [-1.0, 0.0, -1.0],
+                [1.0, 0.0, -1.0],
+                [1.0, 0.0, 1.0],
+                [-1.0, 0.0, 1.0],
+            ],
+        );
+        mesh.set_indices(Some(Indices::U32(vec![0, 1, 2, 0, 2, 3])));
+        let mut collider = MeshCollider::default();
+        collider.build(
+            HandleId::random::<Mesh>(),
+            &mesh,
+            &GlobalTransform::from_xyz(0.0, 1.0, 0.0),
+        );
+        collider
+    }
+
+    /// World space particle system with a single particle at `translation` which moved at
+    /// `velocity` during the last update
+    fn particle_system(translation: Vec3, velocity: Vec3) -> ParticleSystem {
+        let mut particle_system = ParticleSystem {
+            world_space: true,
+            ..Default::default()
+        };
+        particle_system.push(
+            Particle {
+                translation,
+                velocity,
+                ..Default::default()
+            },
+            &GlobalTransform::identity(),
+        );
+        particle_system
+    }
+
+    #[test]
+    fn particle_fired_through_the_quad_bounces() {
+        let collision = ParticleCollision {
+            colliders: vec![],
+            bounce: 1.0,
+            ..Default::default()
+        };
+        let collider = quad_collider();
+        // Fired through both triangles, from above
+        for (translation, velocity) in [
+            (Vec3::new(0.5, 0.8, -0.25), Vec3::new(1.0, -6.0, 0.0)),
+            (Vec3::new(-0.5, 0.8, 0.25), Vec3::new(-1.0, -6.0, 0.0)),
+        ] {
+            let mut particle_system = particle_system(translation, velocity);
+            collision.apply(
+                &mut particle_system,
+                &GlobalTransform::identity(),
+                &[&collider],
+                DELTA_TIME,
+            );
+            assert_eq!(particle_system.collided_particles().len(), 1);
+            let particle = particle_system.get(0).unwrap();
+            let contact = Vec3::new(translation.x, 1.0, translation.z);
+            assert!(particle.translation.abs_diff_eq(contact, 1e-5));
+            let reflected = Vec3::new(velocity.x, 6.0, velocity.z);
+            assert!(particle.velocity.abs_diff_eq(reflected, 1e-5));
+        }
+        // Fired from below, the triangles being two sided
+        let mut particle_system = particle_system(Vec3::new(0.0, 1.2, 0.5), Vec3::Y * 6.0);
+        collision.apply(
+            &mut particle_system,
+            &GlobalTransform::identity(),
+            &[&collider],
+            DELTA_TIME,
+        );
+        let particle = particle_system.get(0).unwrap();
+        assert!(particle
+            .translation
+            .abs_diff_eq(Vec3::new(0.0, 1.0, 0.5), 1e-5));
+        assert!(particle.velocity.abs_diff_eq(Vec3::Y * -6.0, 1e-5));
+    }
+
+    #[test]
+    fn particle_passing_beside_the_quad_does_not_collide() {
+        let collision = ParticleCollision {
+            colliders: vec![],
+            ..Default::default()
+        };
+        let velocity = Vec3::new(0.0, -6.0, 0.0);
+        let mut particle_system = particle_system(Vec3::new(1.5, 0.8, 0.0), velocity);
+        collision.apply(
+            &mut particle_system,
+            &GlobalTransform::identity(),
+            &[&quad_collider()],
+            DELTA_TIME,
+        );
+        assert!(particle_system.collided_particles().is_empty());
+        assert_eq!(particle_system.get(0).unwrap().velocity, velocity);
+    }
+}
@@ -0,0 +1,458 @@
+mod mesh_collider;
+
+use crate::utilities::bvh::TriangleBvh;
+use crate::{ParticleMut, ParticleSystem};
+use bevy::prelude::{Component, GlobalTransform, Reflect, Vec3};
+use bevy::reflect::FromReflect;
+use serde::{Deserialize, Serialize};
+
+pub use mesh_collider::MeshCollider;
+
+/// World space collider for particles
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub enum Collider {
+    /// Infinite plane, particles collide from the side the `normal` points to
+    Plane {
+        /// Any point of the plane
+        point: Vec3,
+        /// The plane normal
+        normal: Vec3,
+    },
+    /// Solid sphere
+    Sphere {
+        /// Sphere center
+        center: Vec3,
+        /// Sphere radius
+        radius: f32,
+    },
+    /// Solid axis aligned box
+    Aabb {
+        /// Box center
+        center: Vec3,
+        /// Box half extents
+        half_extents: Vec3,
+    },
+}
+
+/// Collision of a particle with a [`Collider`]
+#[derive(Debug, Copy, Clone)]
+struct Contact {
+    /// The contact normal
+    normal: Vec3,
+    /// Penetration depth along the `normal`
+    depth: f32,
+}
+
+/// Makes particles collide with world space [`Collider`]s and [`MeshCollider`] entities
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct ParticleCollision {
+    /// The world space colliders
+    pub colliders: Vec<Collider>,
+    /// Should the particles collide with the [`MeshCollider`] entities
+    pub collide_with_meshes: bool,
+    /// Proportion of the velocity kept along the contact normal after a collision (`0` means no
+    /// bounce)
+    pub bounce: f32,
+    /// Proportion of the tangent velocity lost after a collision (`0` means no friction)
+    pub dampen: f32,
+    /// Proportion of the particle start lifetime lost after a collision
+    pub lifetime_loss: f32,
+    /// Particles with a speed below this value after a collision are killed
+    pub min_kill_speed: f32,
+    /// Multiplier of the particle half size to get its collision radius (`0` means the particles
+    /// are points)
+    pub radius_scale: f32,
+}
+
+impl Default for Collider {
+    fn default() -> Self {
+        Self::Plane {
+            point: Vec3::ZERO,
+            normal: Vec3::Y,
+        }
+    }
+}
+
+impl FromReflect for Collider {
+    fn from_reflect(reflect: &dyn Reflect) -> Option<Self> {
+        reflect.any().downcast_ref::<Self>().copied()
+    }
+}
+
+impl Default for ParticleCollision {
+    fn default() -> Self {
+        Self {
+            colliders: vec![Collider::default()],
+            collide_with_meshes: true,
+            bounce: 0.5,
+            dampen: 0.0,
+            lifetime_loss: 0.0,
+            min_kill_speed: 0.0,
+            radius_scale: 0.0,
+        }
+    }
+}
+
+impl Collider {
+    /// Infinite ground plane at `height`
+    #[inline]
+    #[must_use]
+    pub fn ground(height: f32) -> Self {
+        Self::Plane {
+            point: Vec3::new(0.0, height, 0.0),
+            normal: Vec3::Y,
+        }
+    }
+
+    /// Checks if a sphere at `position` with `radius` penetrates the collider
+    fn contact(&self, position: Vec3, radius: f32) -> Option<Contact> {
+        match *self {
+            Self::Plane { point, normal } => {
+                let normal = normal.try_normalize()?;
+                let distance = (position - point).dot(normal) - radius;
+                (distance < 0.0).then(|| Contact {
+                    normal,
+                    depth: -distance,
+                })
+            }
+            Self::Sphere { center, radius: r } => {
+                let delta = position - center;
+                let distance = delta.length();
+                let depth = r + radius - distance;
+                (depth > 0.0).then(|| Contact {
+                    normal: delta.try_normalize().unwrap_or(Vec3::Y),
+                    depth,
+                })
+            }
+            Self::Aabb {
+                center,
+                half_extents,
+            } => {
+                let delta = position - center;
+                let penetration = half_extents + radius - delta.abs();
+                if penetration.min_element() <= 0.0 {
+                    return None;
+                }
+                let (axis, depth) =
+                    if penetration.x < penetration.y && penetration.x < penetration.z {
+                        (Vec3::X, penetration.x)
+                    } else if penetration.y < penetration.z {
+                        (Vec3::Y, penetration.y)
+                    } else {
+                        (Vec3::Z, penetration.z)
+                    };
+                let sign = if delta.dot(axis) < 0.0 { -1.0 } else { 1.0 };
+                Some(Contact {
+                    normal: axis * sign,
+                    depth,
+                })
+            }
+        }
+    }
+}
+
+/// Checks if a sphere with `radius` moving from `previous` to `position` hits a triangle of `bvh`.
+///
+/// Triangles are two sided, the contact normal faces the `previous` position
+fn mesh_contact(bvh: &TriangleBvh, previous: Vec3, position: Vec3, radius: f32) -> Option<Contact> {
+    let min = previous.min(position) - radius;
+    let max = previous.max(position) + radius;
+    let mut closest_hit: Option<(f32, Contact)> = None;
+    bvh.query(min, max, |triangle| {
+        let normal = match triangle.normal() {
+            Some(normal) if (previous - triangle.0[0]).dot(normal) < 0.0 => -normal,
+            Some(normal) => normal,
+            None => return,
+        };
+        let hit = triangle
+            .intersect_segment(previous, position)
+            .map(|ratio| {
+                // The particle went through the triangle
+                let distance = (position - triangle.0[0]).dot(normal);
+                (
+                    ratio,
+                    Contact {
+                        normal,
+                        depth: radius - distance,
+                    },
+                )
+            })
+            .or_else(|| {
+                let delta = position - triangle.closest_point(position);
+                let distance = delta.length();
+                (distance < radius).then(|| {
+                    (
+                        1.0,
+                        Contact {
+                            normal: delta.try_normalize().unwrap_or(normal),
+                            depth: radius - distance,
+                        },
+                    )
+                })
+            });
+        if let Some((ratio, contact)) = hit {
+            match closest_hit {
+                Some((closest, _)) if closest <= ratio => (),
+                _ => closest_hit = Some((ratio, contact)),
+            }
+        }
+    });
+    closest_hit.map(|(_, contact)| contact)
+}
+
+impl ParticleCollision {
+    /// Moves `particle` out of the contact and applies the collision response if the particle moves
+    /// towards the collider. Particles resting on the collider or moving away from it are only
+    /// moved out of the contact.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the particle collided
+    fn resolve(&self, particle: &mut ParticleMut<'_>, contact: Contact) -> bool {
+        *particle.translation += contact.normal * contact.depth;
+        let normal_speed = particle.velocity.dot(contact.normal);
+        if normal_speed >= 0.0 {
+            return false;
+        }
+        let normal_velocity = contact.normal * normal_speed;
+        let tangent_velocity = *particle.velocity - normal_velocity;
+        *particle.velocity = tangent_velocity * (1.0 - self.dampen) - normal_velocity * self.bounce;
+        *particle.lifetime -= *particle.start_lifetime * self.lifetime_loss;
+        if particle.speed() < self.min_kill_speed {
+            *particle.lifetime = 0.0;
+        }
+        true
+    }
+
+    /// Collides a single particle, expressed in world space, with every collider.
+    ///
+    /// # Arguments
+    ///
+    /// * `particle` - The particle to collide, in world space
+    /// * `meshes` - The mesh colliders hierarchies
+    /// * `delta_time` - The delta time of the last particle update, used to retrieve the previous
+    ///   particle position
+    ///
+    /// # Returns
+    ///
+    /// Whether the particle touched a collider, and whether it collided with one
+    fn collide(
+        &self,
+        particle: &mut ParticleMut<'_>,
+        meshes: &[&TriangleBvh],
+        delta_time: f32,
+    ) -> (bool, bool) {
+        let radius = particle.size.max_element() * 0.5 * self.radius_scale;
+        // Retrieved before any contact moves the particle or changes its velocity
+        let previous = *particle.translation - *particle.velocity * delta_time;
+        let (mut touched, mut collided) = (false, false);
+        for collider in &self.colliders {
+            if let Some(contact) = collider.contact(*particle.translation, radius) {
+                collided |= self.resolve(particle, contact);
+                touched = true;
+            }
+        }
+        for bvh in meshes {
+            if let Some(contact) = mesh_contact(bvh, previous, *particle.translation, radius) {
+                collided |= self.resolve(particle, contact);
+                touched = true;
+            }
+        }
+        (touched, collided)
+    }
+
+    /// Collides every particle of `particle_system`
+    ///
+    /// # Arguments
+    ///
+    /// * `particle_system` - The particle system to apply the collisions to
+    /// * `transform` - The particle system global transform, which will be used to compute the
+    ///   world space particle `translation` if the system is not in
+    ///   [`ParticleSystem::world_space`] mode
+    /// * `mesh_colliders` - The mesh colliders, ignored if `collide_with_meshes` is disabled
+    /// * `delta_time` - The delta time of the last particle update
+    pub fn apply(
+        &self,
+        particle_system: &mut ParticleSystem,
+        transform: &GlobalTransform,
+        mesh_colliders: &[&MeshCollider],
+        delta_time: f32,
+    ) {
+        particle_system.collided_particles.clear();
+        let meshes: Vec<&TriangleBvh> = if self.collide_with_meshes {
+            mesh_colliders.iter().filter_map(|c| c.bvh()).collect()
+        } else {
+            Vec::new()
+        };
+        let matrix = transform.compute_matrix();
+        let inverse = matrix.inverse();
+        let ParticleSystem {
+            world_space,
+            particles,
+            collided_particles,
+            ..
+        } = particle_system;
+        for mut particle in particles.as_slice_mut().iter_mut() {
+            let collided = if *world_space {
+                self.collide(&mut particle, &meshes, delta_time).1
+            } else {
+                let mut world_particle = particle.to_particle().transformed(&matrix);
+                let (touched, collided) =
+                    self.collide(&mut world_particle.as_particle_mut(), &meshes, delta_time);
+                if touched {
+                    particle.set(world_particle.transformed(&inverse));
+                }
+                collided
+            };
+            if collided {
+                collided_particles.push(particle.to_particle());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Particle;
+
+    fn collision() -> ParticleCollision {
+        ParticleCollision {
+            colliders: vec![],
+            bounce: 0.5,
+            lifetime_loss: 0.25,
+            ..Default::default()
+        }
+    }
+
+    fn particle(translation: Vec3, velocity: Vec3) -> Particle {
+        Particle {
+            translation,
+            velocity,
+            lifetime: 1.5,
+            start_lifetime: 2.0,
+            ..Default::default()
+        }
+    }
+
+    /// Collides `particle` with `collider`, returning whether it touched and collided with it
+    fn collide(
+        collision: &ParticleCollision,
+        collider: Collider,
+        particle: &mut Particle,
+    ) -> (bool, bool) {
+        let collision = ParticleCollision {
+            colliders: vec![collider],
+            ..collision.clone()
+        };
+        collision.collide(&mut particle.as_particle_mut(), &[], 0.0)
+    }
+
+    #[test]
+    fn plane_bounce_and_lifetime_loss() {
+        let mut particle = particle(Vec3::new(0.0, -0.1, 0.0), Vec3::new(1.0, -2.0, 0.0));
+        let result = collide(&collision(), Collider::ground(0.0), &mut particle);
+        assert_eq!(result, (true, true));
+        assert!(particle.translation.abs_diff_eq(Vec3::ZERO, 1e-6));
+        assert!(particle
+            .velocity
+            .abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1e-6));
+        assert!((particle.lifetime - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sphere_bounce() {
+        let collider = Collider::Sphere {
+            center: Vec3::ZERO,
+            radius: 1.0,
+        };
+        let mut particle = particle(Vec3::new(0.9, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        assert_eq!(collide(&collision(), collider, &mut particle), (true, true));
+        assert!(particle.translation.abs_diff_eq(Vec3::X, 1e-6));
+        assert!(particle
+            .velocity
+            .abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-6));
+        assert!((particle.lifetime - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn aabb_bounce_on_the_closest_face() {
+        let collider = Collider::Aabb {
+            center: Vec3::ZERO,
+            half_extents: Vec3::ONE,
+        };
+        let mut particle = particle(Vec3::new(0.2, -0.95, 0.0), Vec3::new(1.0, 1.0, 0.0));
+        let collision = ParticleCollision {
+            dampen: 0.5,
+            ..collision()
+        };
+        assert_eq!(collide(&collision, collider, &mut particle), (true, true));
+        assert!(particle
+            .translation
+            .abs_diff_eq(Vec3::new(0.2, -1.0, 0.0), 1e-6));
+        assert!(particle
+            .velocity
+            .abs_diff_eq(Vec3::new(0.5, -0.5, 0.0), 1e-6));
+    }
+
+    #[test]
+    fn particle_radius() {
+        let mut particle = Particle {
+            size: Vec3::splat(0.4),
+            ..particle(Vec3::new(0.0, 0.15, 0.0), Vec3::new(0.0, -1.0, 0.0))
+        };
+        let collision = ParticleCollision {
+            radius_scale: 1.0,
+            ..collision()
+        };
+        assert_eq!(
+            collide(&collision, Collider::ground(0.0), &mut particle),
+            (true, true)
+        );
+        assert!(particle
+            .translation
+            .abs_diff_eq(Vec3::new(0.0, 0.2, 0.0), 1e-6));
+    }
+
+    #[test]
+    fn only_particles_moving_towards_the_collider_collide() {
+        let colliders = [
+            Collider::ground(0.0),
+            Collider::Sphere {
+                center: Vec3::new(0.0, -1.0, 0.0),
+                radius: 1.0,
+            },
+            Collider::Aabb {
+                center: Vec3::new(0.0, -1.0, 0.0),
+                half_extents: Vec3::ONE,
+            },
+        ];
+        for collider in colliders {
+            for velocity in [Vec3::new(1.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0)] {
+                let mut particle = particle(Vec3::new(0.0, -0.1, 0.0), velocity);
+                // The particle is moved out of the collider without any collision response
+                assert_eq!(
+                    collide(&collision(), collider, &mut particle),
+                    (true, false)
+                );
+                assert!(particle.translation.abs_diff_eq(Vec3::ZERO, 1e-6));
+                assert_eq!(particle.velocity, velocity);
+                assert!((particle.lifetime - 1.5).abs() < f32::EPSILON);
+            }
+        }
+    }
+
+    #[test]
+    fn slow_particles_are_killed() {
+        let mut particle = particle(Vec3::new(0.0, -0.1, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let collision = ParticleCollision {
+            min_kill_speed: 1.0,
+            ..collision()
+        };
+        collide(&collision, Collider::ground(0.0), &mut particle);
+        assert!(particle.lifetime <= 0.0);
+    }
+}
@@ -1,15 +1,16 @@
 use crate::modifiers::ParticleModifier;
-use crate::{ColorGradient, Particle};
+use crate::{ColorGradient, Curve, ParticleMut};
 use bevy::prelude::{Component, Reflect};
+use serde::{Deserialize, Serialize};
 
 /// Evaluates particle color over its lifetime
-#[derive(Debug, Clone, Default, Component, Reflect)]
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct ColorOverLifeTime(pub ColorGradient);
 
 impl ParticleModifier for ColorOverLifeTime {
-    fn apply(&self, particle: &mut Particle, _delta_time: f32) {
-        particle.color = self.0.evaluate(particle.alive_time_ratio());
+    fn apply(&self, particle: &mut ParticleMut<'_>, _delta_time: f32) {
+        *particle.color = self.0.evaluate(particle.alive_time_ratio());
     }
 }
 
@@ -20,7 +21,7 @@ impl From<ColorGradient> for ColorOverLifeTime {
 }
 
 /// Evaluates particle color according to its speed
-#[derive(Debug, Clone, Default, Component, Reflect)]
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct ColorOverSpeed {
     /// Color gradient
@@ -30,10 +31,10 @@ pub struct ColorOverSpeed {
 }
 
 impl ParticleModifier for ColorOverSpeed {
-    fn apply(&self, particle: &mut Particle, _delta_time: f32) {
+    fn apply(&self, particle: &mut ParticleMut<'_>, _delta_time: f32) {
         let delta =
             (particle.speed() - self.speed_range.0) / (self.speed_range.1 - self.speed_range.0);
-        particle.color = self.gradient.evaluate(delta);
+        *particle.color = self.gradient.evaluate(delta);
     }
 }
 
@@ -45,3 +46,23 @@ impl From<ColorGradient> for ColorOverSpeed {
         }
     }
 }
+
+/// **Sets** particle color alpha over its lifetime, using a curve evaluated on the particle alive
+/// time ratio
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct AlphaOverLifetime(pub Curve);
+
+impl ParticleModifier for AlphaOverLifetime {
+    fn apply(&self, particle: &mut ParticleMut<'_>, _delta_time: f32) {
+        particle
+            .color
+            .set_a(self.0.evaluate(particle.alive_time_ratio()).clamp(0.0, 1.0));
+    }
+}
+
+impl From<Curve> for AlphaOverLifetime {
+    fn from(curve: Curve) -> Self {
+        Self(curve)
+    }
+}
@@ -1,9 +1,14 @@
 use crate::modifiers::{ParticleModifier, ParticleSystemModifier};
-use crate::{Particle, ParticleSystem};
-use bevy::prelude::{Component, Reflect};
+use crate::{ParticleMut, ParticleSystem};
+use bevy::prelude::{Component, Reflect, Vec3};
+use serde::{Deserialize, Serialize};
 
-/// Constraints [`ParticleSystem`] particle system count to a max value
-#[derive(Debug, Copy, Clone, Component, Reflect)]
+/// Constraints [`ParticleSystem`] particle system count to a max value, removing the oldest
+/// particles first.
+///
+/// The removed particles are added to the [`ParticleSystem::dead_particles`], triggering the death
+/// sub emitters, but no [`ParticleDied`](crate::ParticleDied) event is sent for them
+#[derive(Debug, Copy, Clone, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct MaxParticleCount(pub usize);
 
@@ -14,16 +19,16 @@ impl Default for MaxParticleCount {
 }
 
 impl ParticleSystemModifier for MaxParticleCount {
-    fn apply(&self, particles: &mut ParticleSystem, _: f32) {
-        let delta = particles.len().saturating_sub(self.0);
+    fn apply(&self, particle_system: &mut ParticleSystem, _: f32) {
+        let delta = particle_system.len().saturating_sub(self.0);
         if delta > 0 {
-            particles.particles.drain(0..delta);
+            particle_system.remove_oldest(delta);
         }
     }
 }
 
 /// Constraints [`ParticleSystem`] particle system count to a max speed
-#[derive(Debug, Copy, Clone, Component, Reflect)]
+#[derive(Debug, Copy, Clone, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct MaxParticleSpeed(pub f32);
 
@@ -34,28 +39,26 @@ impl Default for MaxParticleSpeed {
 }
 
 impl ParticleModifier for MaxParticleSpeed {
-    fn apply(&self, particle: &mut Particle, _: f32) {
+    fn apply(&self, particle: &mut ParticleMut<'_>, _: f32) {
         if particle.speed() > self.0 {
-            particle.velocity = particle.velocity.normalize() * self.0;
+            *particle.velocity = particle.velocity.normalize() * self.0;
         }
     }
 }
 
-/// Constraints [`ParticleSystem`] particle system count to a max speed
-#[derive(Debug, Copy, Clone, Component, Reflect)]
+/// Constraints [`ParticleSystem`] particles to a max size on each axis
+#[derive(Debug, Copy, Clone, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
-pub struct MaxParticleSize(pub f32);
+pub struct MaxParticleSize(pub Vec3);
 
 impl Default for MaxParticleSize {
     fn default() -> Self {
-        Self(100.0)
+        Self(Vec3::splat(100.0))
     }
 }
 
 impl ParticleModifier for MaxParticleSize {
-    fn apply(&self, particle: &mut Particle, _: f32) {
-        if particle.size > self.0 {
-            particle.size = self.0;
-        }
+    fn apply(&self, particle: &mut ParticleMut<'_>, _: f32) {
+        *particle.size = particle.size.min(self.0);
     }
 }
@@ -1,9 +1,10 @@
 use crate::modifiers::ParticleModifier;
-use crate::Particle;
+use crate::ParticleMut;
 use bevy::prelude::{Component, Reflect, Vec3};
+use serde::{Deserialize, Serialize};
 
 /// Gravity for particles
-#[derive(Debug, Clone, Component, Reflect)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct ParticleGravity(pub Vec3);
 
@@ -15,8 +16,8 @@ impl Default for ParticleGravity {
 
 impl ParticleModifier for ParticleGravity {
     #[inline]
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
-        particle.velocity += self.0 * delta_time;
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        *particle.velocity += self.0 * delta_time;
     }
 }
 
@@ -1,26 +1,30 @@
+mod collision;
 mod color;
 mod constraints;
 mod gravity;
 mod perlin_noise;
 mod rotation;
 mod size;
+mod stack;
 mod velocity;
 
 use crate::ParticleSystem;
 use bevy::prelude::Component;
 use rand::Rng;
 
-use crate::particle::Particle;
+use crate::particle::ParticleMut;
 pub use {
-    color::{ColorOverLifeTime, ColorOverSpeed},
+    collision::{Collider, MeshCollider, ParticleCollision},
+    color::{AlphaOverLifetime, ColorOverLifeTime, ColorOverSpeed},
     constraints::{MaxParticleCount, MaxParticleSize, MaxParticleSpeed},
     gravity::ParticleGravity,
     perlin_noise::{NoiseQuality, PerlinNoise},
-    rotation::{RotationOverTime, RotationOverVelocity},
-    size::{SizeOverSpeed, SizeOverTime},
+    rotation::{RotationOverLifetime, RotationOverTime, RotationOverTime3d, RotationOverVelocity},
+    size::{SizeOverLifetime, SizeOverSpeed, SizeOverTime},
+    stack::{BuiltinModifiers, DynParticleModifier, ParticleModifiers, StackedModifier},
     velocity::{
-        AngularVelocityOverTime, LinearVelocityOverLifeTime, OrbitalVelocityOverLifeTime,
-        SpeedOverTime, VelocityOverTime,
+        AngularVelocityOverTime, AngularVelocityOverTime3d, LinearVelocityOverLifeTime,
+        OrbitalVelocityOverLifeTime, SpeedOverLifetime, SpeedOverTime, VelocityOverTime,
     },
 };
 
@@ -33,11 +37,11 @@ pub trait ParticleSystemModifier: Component {
 /// Common trait for particle modifiers
 pub trait ParticleModifier: Component {
     /// Applies modification to the particle
-    fn apply(&self, particle: &mut Particle, delta_time: f32);
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32);
 }
 
 /// Common trait for particle modifiers needing access to a randomizer
 pub trait ParticleRngModifier: Component {
     /// Applies modification to the particle
-    fn apply(&self, rng: &mut impl Rng, particle: &mut Particle, delta_time: f32);
+    fn apply(&self, rng: &mut impl Rng, particle: &mut ParticleMut<'_>, delta_time: f32);
 }
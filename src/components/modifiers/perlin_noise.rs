@@ -1,12 +1,13 @@
 use crate::modifiers::ParticleRngModifier;
-use crate::{Particle, Vec3};
+use crate::{ParticleMut, Vec3};
 use bevy::math::{DVec2, DVec3};
 use bevy::prelude::Component;
 use noise::{NoiseFn, Perlin};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// Defines the quality of the perlin noise
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum NoiseQuality {
     /// 2D Noise (fast)
@@ -22,7 +23,7 @@ pub enum NoiseQuality {
 }
 
 /// Defines the quality of the perlin noise
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum VelocityInfluence {
     /// One noise value will be applied to all axis
@@ -32,10 +33,11 @@ pub enum VelocityInfluence {
 }
 
 /// Perlin Noise modifier for particles
-#[derive(Debug, Clone, Component)]
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct PerlinNoise {
     #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    #[serde(skip)]
     noise: Perlin,
     /// Noise quality
     pub quality: NoiseQuality,
@@ -92,16 +94,16 @@ impl PerlinNoise {
 }
 
 impl ParticleRngModifier for PerlinNoise {
-    fn apply(&self, rng: &mut impl Rng, particle: &mut Particle, delta_time: f32) {
+    fn apply(&self, rng: &mut impl Rng, particle: &mut ParticleMut<'_>, delta_time: f32) {
         let x = self.get_value(rng);
         particle.try_rotate(x * delta_time * self.rotation_influence);
-        particle.size += x * delta_time * self.size_influence;
+        *particle.size += Vec3::splat(x * delta_time * self.size_influence);
         match self.velocity_influence {
             VelocityInfluence::Uniform(influence) => {
-                particle.velocity += x * influence * delta_time;
+                *particle.velocity += x * influence * delta_time;
             }
             VelocityInfluence::SeparateAxis(influence) => {
-                particle.velocity +=
+                *particle.velocity +=
                     influence * Vec3::new(x, self.get_value(rng), self.get_value(rng)) * delta_time;
             }
         }
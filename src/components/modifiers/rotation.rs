@@ -1,20 +1,34 @@
 use crate::modifiers::ParticleModifier;
-use crate::Particle;
-use bevy::prelude::{Component, Reflect};
+use crate::{Curve, ParticleMut};
+use bevy::prelude::{Component, Reflect, Vec3};
+use serde::{Deserialize, Serialize};
 
 /// Increases particle rotation over time
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct RotationOverTime(pub f32);
 
 impl ParticleModifier for RotationOverTime {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
         particle.try_rotate(delta_time * self.0);
     }
 }
 
+/// Increases particle 3D rotation over time, in radians per second around the particle local axes
+///
+/// Note: Particles with a 1D rotation only use the `z` axis
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct RotationOverTime3d(pub Vec3);
+
+impl ParticleModifier for RotationOverTime3d {
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        particle.try_rotate_3d(self.0 * delta_time);
+    }
+}
+
 /// Increases particle rotation over its velocity
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct RotationOverVelocity {
     /// The rotation coefficient
@@ -24,7 +38,7 @@ pub struct RotationOverVelocity {
 }
 
 impl ParticleModifier for RotationOverVelocity {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
         let mut dir = particle.non_zero_direction().x;
         if self.abs {
             dir = dir.abs();
@@ -32,3 +46,22 @@ impl ParticleModifier for RotationOverVelocity {
         particle.try_rotate(dir.signum() * particle.speed() * delta_time * self.value);
     }
 }
+
+/// Rotates particles with an angular velocity (in radians per second) defined by a curve evaluated
+/// on the particle alive time ratio
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct RotationOverLifetime(pub Curve);
+
+impl ParticleModifier for RotationOverLifetime {
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        let angular_velocity = self.0.evaluate(particle.alive_time_ratio());
+        particle.try_rotate(angular_velocity * delta_time);
+    }
+}
+
+impl From<Curve> for RotationOverLifetime {
+    fn from(curve: Curve) -> Self {
+        Self(curve)
+    }
+}
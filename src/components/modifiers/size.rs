@@ -1,37 +1,70 @@
 use crate::modifiers::ParticleModifier;
-use crate::Particle;
-use bevy::prelude::{Component, Reflect};
+use crate::{Curve, ParticleMut};
+use bevy::prelude::{Component, Reflect, Vec3};
+use serde::{Deserialize, Serialize};
 
-/// Increases particle size over time
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+/// Increases particle size over time, on each axis. A negative value shrinks the particles, down
+/// to a size of `0`
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
-pub struct SizeOverTime(pub f32);
+pub struct SizeOverTime(pub Vec3);
 
 impl ParticleModifier for SizeOverTime {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
-        particle.size += delta_time * self.0;
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        *particle.size = (*particle.size + delta_time * self.0).max(Vec3::ZERO);
     }
 }
 
 impl From<f32> for SizeOverTime {
     fn from(v: f32) -> Self {
+        Self(Vec3::splat(v))
+    }
+}
+
+impl From<Vec3> for SizeOverTime {
+    fn from(v: Vec3) -> Self {
         Self(v)
     }
 }
 
-/// Increases particle size over its speed
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+/// Increases particle size over its speed, on each axis. A negative value shrinks the particles,
+/// down to a size of `0`
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
-pub struct SizeOverSpeed(pub f32);
+pub struct SizeOverSpeed(pub Vec3);
 
 impl ParticleModifier for SizeOverSpeed {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
-        particle.size += particle.speed() * delta_time * self.0;
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        *particle.size = (*particle.size + particle.speed() * delta_time * self.0).max(Vec3::ZERO);
     }
 }
 
 impl From<f32> for SizeOverSpeed {
     fn from(v: f32) -> Self {
+        Self(Vec3::splat(v))
+    }
+}
+
+impl From<Vec3> for SizeOverSpeed {
+    fn from(v: Vec3) -> Self {
         Self(v)
     }
 }
+
+/// **Sets** particle size over its lifetime to its start size multiplied by a curve evaluated on
+/// the particle alive time ratio
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct SizeOverLifetime(pub Curve);
+
+impl ParticleModifier for SizeOverLifetime {
+    fn apply(&self, particle: &mut ParticleMut<'_>, _delta_time: f32) {
+        *particle.size = *particle.start_size * self.0.evaluate(particle.alive_time_ratio());
+    }
+}
+
+impl From<Curve> for SizeOverLifetime {
+    fn from(curve: Curve) -> Self {
+        Self(curve)
+    }
+}
@@ -0,0 +1,351 @@
+use crate::modifiers::{
+    AlphaOverLifetime, AngularVelocityOverTime, AngularVelocityOverTime3d, ColorOverLifeTime,
+    ColorOverSpeed, LinearVelocityOverLifeTime, MaxParticleSize, MaxParticleSpeed,
+    OrbitalVelocityOverLifeTime, ParticleGravity, ParticleModifier, ParticleRngModifier,
+    PerlinNoise, RotationOverLifetime, RotationOverTime, RotationOverTime3d, RotationOverVelocity,
+    SizeOverLifetime, SizeOverSpeed, SizeOverTime, SpeedOverLifetime, SpeedOverTime,
+    VelocityOverTime,
+};
+use crate::particle::ParticleSliceMut;
+use crate::{ParticleMut, ParticleSystem};
+use bevy::ecs::query::WorldQuery;
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::{Component, Reflect};
+use rand::rngs::SmallRng;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Object safe particle modifier, allowing custom modifiers in a [`ParticleModifiers`] stack.
+///
+/// Use [`StackedModifier::custom`] or [`StackedModifier::custom_rng`] to wrap a
+/// [`ParticleModifier`] or a [`ParticleRngModifier`]
+pub trait DynParticleModifier: Debug + Send + Sync + 'static {
+    /// Applies modification to the particle
+    fn apply(&self, rng: &mut SmallRng, particle: &mut ParticleMut<'_>, delta_time: f32);
+}
+
+/// Wraps a [`ParticleModifier`] as a [`DynParticleModifier`]
+#[derive(Debug)]
+struct CustomModifier<M>(M);
+
+/// Wraps a [`ParticleRngModifier`] as a [`DynParticleModifier`]
+#[derive(Debug)]
+struct CustomRngModifier<M>(M);
+
+/// Modifier of a [`ParticleModifiers`] stack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum StackedModifier {
+    /// [`MaxParticleSize`] modifier
+    MaxParticleSize(MaxParticleSize),
+    /// [`MaxParticleSpeed`] modifier
+    MaxParticleSpeed(MaxParticleSpeed),
+    /// [`ParticleGravity`] modifier
+    ParticleGravity(ParticleGravity),
+    /// [`SpeedOverTime`] modifier
+    SpeedOverTime(SpeedOverTime),
+    /// [`SpeedOverLifetime`] modifier
+    SpeedOverLifetime(SpeedOverLifetime),
+    /// [`VelocityOverTime`] modifier
+    VelocityOverTime(VelocityOverTime),
+    /// [`AngularVelocityOverTime`] modifier
+    AngularVelocityOverTime(AngularVelocityOverTime),
+    /// [`AngularVelocityOverTime3d`] modifier
+    AngularVelocityOverTime3d(AngularVelocityOverTime3d),
+    /// [`OrbitalVelocityOverLifeTime`] modifier
+    OrbitalVelocityOverLifeTime(OrbitalVelocityOverLifeTime),
+    /// [`LinearVelocityOverLifeTime`] modifier
+    LinearVelocityOverLifeTime(LinearVelocityOverLifeTime),
+    /// [`SizeOverTime`] modifier
+    SizeOverTime(SizeOverTime),
+    /// [`SizeOverSpeed`] modifier
+    SizeOverSpeed(SizeOverSpeed),
+    /// [`SizeOverLifetime`] modifier
+    SizeOverLifetime(SizeOverLifetime),
+    /// [`RotationOverVelocity`] modifier
+    RotationOverVelocity(RotationOverVelocity),
+    /// [`RotationOverTime`] modifier
+    RotationOverTime(RotationOverTime),
+    /// [`RotationOverTime3d`] modifier
+    RotationOverTime3d(RotationOverTime3d),
+    /// [`RotationOverLifetime`] modifier
+    RotationOverLifetime(RotationOverLifetime),
+    /// [`ColorOverLifeTime`] modifier
+    ColorOverLifeTime(ColorOverLifeTime),
+    /// [`ColorOverSpeed`] modifier
+    ColorOverSpeed(ColorOverSpeed),
+    /// [`AlphaOverLifetime`] modifier
+    AlphaOverLifetime(AlphaOverLifetime),
+    /// [`PerlinNoise`] modifier
+    PerlinNoise(PerlinNoise),
+    /// Custom modifier, can't be serialized
+    #[serde(skip)]
+    Custom(Arc<dyn DynParticleModifier>),
+}
+
+/// Ordered stack of particle modifiers, applied in a single pass over the particles.
+///
+/// The separate built-in modifier components are applied in the same pass, in a fixed order,
+/// before the stack. Use the stack to control the modifiers order or to add custom modifiers.
+///
+/// Note: System-wide modifiers like [`MaxParticleCount`](crate::modifiers::MaxParticleCount) or
+/// [`ParticleCollision`](crate::modifiers::ParticleCollision) are only available as components
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[reflect_value(Component)]
+pub struct ParticleModifiers(pub Vec<StackedModifier>);
+
+/// Built-in per particle modifier components of a particle system, applied in a single pass
+#[derive(WorldQuery)]
+pub struct BuiltinModifiers<'w> {
+    max_particle_size: Option<&'w MaxParticleSize>,
+    particle_gravity: Option<&'w ParticleGravity>,
+    max_particle_speed: Option<&'w MaxParticleSpeed>,
+    speed_over_time: Option<&'w SpeedOverTime>,
+    speed_over_lifetime: Option<&'w SpeedOverLifetime>,
+    velocity_over_time: Option<&'w VelocityOverTime>,
+    linear_velocity_over_lifetime: Option<&'w LinearVelocityOverLifeTime>,
+    orbital_velocity_over_lifetime: Option<&'w OrbitalVelocityOverLifeTime>,
+    angular_velocity_over_time: Option<&'w AngularVelocityOverTime>,
+    angular_velocity_over_time_3d: Option<&'w AngularVelocityOverTime3d>,
+    size_over_time: Option<&'w SizeOverTime>,
+    size_over_speed: Option<&'w SizeOverSpeed>,
+    size_over_lifetime: Option<&'w SizeOverLifetime>,
+    rotation_over_velocity: Option<&'w RotationOverVelocity>,
+    rotation_over_time: Option<&'w RotationOverTime>,
+    rotation_over_time_3d: Option<&'w RotationOverTime3d>,
+    rotation_over_lifetime: Option<&'w RotationOverLifetime>,
+    color_over_lifetime: Option<&'w ColorOverLifeTime>,
+    color_over_speed: Option<&'w ColorOverSpeed>,
+    alpha_over_lifetime: Option<&'w AlphaOverLifetime>,
+    perlin_noise: Option<&'w PerlinNoise>,
+}
+
+impl<M: ParticleModifier + Debug> DynParticleModifier for CustomModifier<M> {
+    #[inline]
+    fn apply(&self, _rng: &mut SmallRng, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        self.0.apply(particle, delta_time);
+    }
+}
+
+impl<M: ParticleRngModifier + Debug> DynParticleModifier for CustomRngModifier<M> {
+    #[inline]
+    fn apply(&self, rng: &mut SmallRng, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        self.0.apply(rng, particle, delta_time);
+    }
+}
+
+impl StackedModifier {
+    /// Wraps a custom [`ParticleModifier`]
+    #[must_use]
+    pub fn custom(modifier: impl ParticleModifier + Debug) -> Self {
+        Self::Custom(Arc::new(CustomModifier(modifier)))
+    }
+
+    /// Wraps a custom [`ParticleRngModifier`]
+    #[must_use]
+    pub fn custom_rng(modifier: impl ParticleRngModifier + Debug) -> Self {
+        Self::Custom(Arc::new(CustomRngModifier(modifier)))
+    }
+
+    /// Applies the modifier to `particle`
+    #[inline]
+    pub fn apply(&self, rng: &mut SmallRng, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        match self {
+            Self::MaxParticleSize(m) => m.apply(particle, delta_time),
+            Self::MaxParticleSpeed(m) => m.apply(particle, delta_time),
+            Self::ParticleGravity(m) => m.apply(particle, delta_time),
+            Self::SpeedOverTime(m) => m.apply(particle, delta_time),
+            Self::SpeedOverLifetime(m) => m.apply(particle, delta_time),
+            Self::VelocityOverTime(m) => m.apply(particle, delta_time),
+            Self::AngularVelocityOverTime(m) => m.apply(particle, delta_time),
+            Self::AngularVelocityOverTime3d(m) => m.apply(particle, delta_time),
+            Self::OrbitalVelocityOverLifeTime(m) => m.apply(rng, particle, delta_time),
+            Self::LinearVelocityOverLifeTime(m) => m.apply(rng, particle, delta_time),
+            Self::SizeOverTime(m) => m.apply(particle, delta_time),
+            Self::SizeOverSpeed(m) => m.apply(particle, delta_time),
+            Self::SizeOverLifetime(m) => m.apply(particle, delta_time),
+            Self::RotationOverVelocity(m) => m.apply(particle, delta_time),
+            Self::RotationOverTime(m) => m.apply(particle, delta_time),
+            Self::RotationOverTime3d(m) => m.apply(particle, delta_time),
+            Self::RotationOverLifetime(m) => m.apply(particle, delta_time),
+            Self::ColorOverLifeTime(m) => m.apply(particle, delta_time),
+            Self::ColorOverSpeed(m) => m.apply(particle, delta_time),
+            Self::AlphaOverLifetime(m) => m.apply(particle, delta_time),
+            Self::PerlinNoise(m) => m.apply(rng, particle, delta_time),
+            Self::Custom(m) => m.apply(rng, particle, delta_time),
+        }
+    }
+}
+
+impl BuiltinModifiersItem<'_> {
+    /// Is there no built-in modifier to apply
+    pub const fn is_empty(&self) -> bool {
+        self.max_particle_size.is_none()
+            && self.particle_gravity.is_none()
+            && self.max_particle_speed.is_none()
+            && self.speed_over_time.is_none()
+            && self.speed_over_lifetime.is_none()
+            && self.velocity_over_time.is_none()
+            && self.linear_velocity_over_lifetime.is_none()
+            && self.orbital_velocity_over_lifetime.is_none()
+            && self.angular_velocity_over_time.is_none()
+            && self.angular_velocity_over_time_3d.is_none()
+            && self.size_over_time.is_none()
+            && self.size_over_speed.is_none()
+            && self.size_over_lifetime.is_none()
+            && self.rotation_over_velocity.is_none()
+            && self.rotation_over_time.is_none()
+            && self.rotation_over_time_3d.is_none()
+            && self.rotation_over_lifetime.is_none()
+            && self.color_over_lifetime.is_none()
+            && self.color_over_speed.is_none()
+            && self.alpha_over_lifetime.is_none()
+            && self.perlin_noise.is_none()
+    }
+
+    /// Applies every built-in modifier to `particle`
+    #[inline]
+    pub fn apply(&self, rng: &mut SmallRng, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        macro_rules! apply {
+            ($($field:ident),*) => {
+                $(if let Some(m) = self.$field {
+                    m.apply(particle, delta_time);
+                })*
+            };
+        }
+        apply!(
+            max_particle_size,
+            particle_gravity,
+            max_particle_speed,
+            speed_over_time,
+            speed_over_lifetime,
+            velocity_over_time
+        );
+        if let Some(m) = self.linear_velocity_over_lifetime {
+            m.apply(rng, particle, delta_time);
+        }
+        if let Some(m) = self.orbital_velocity_over_lifetime {
+            m.apply(rng, particle, delta_time);
+        }
+        apply!(
+            angular_velocity_over_time,
+            angular_velocity_over_time_3d,
+            size_over_time,
+            size_over_speed,
+            size_over_lifetime,
+            rotation_over_velocity,
+            rotation_over_time,
+            rotation_over_time_3d,
+            rotation_over_lifetime,
+            color_over_lifetime,
+            color_over_speed,
+            alpha_over_lifetime
+        );
+        if let Some(m) = self.perlin_noise {
+            m.apply(rng, particle, delta_time);
+        }
+    }
+}
+
+impl ParticleModifiers {
+    /// Applies every modifier of the stack, in order, to each particle of `particle_system`
+    pub fn apply(&self, particle_system: &mut ParticleSystem, rng: &mut SmallRng, delta_time: f32) {
+        if self.0.is_empty() {
+            return;
+        }
+        self.apply_to(particle_system.particles.as_slice_mut(), rng, delta_time);
+    }
+
+    /// Applies every modifier of the stack, in order, to `particle`
+    #[inline]
+    pub fn apply_to_particle(
+        &self,
+        rng: &mut SmallRng,
+        particle: &mut ParticleMut<'_>,
+        delta_time: f32,
+    ) {
+        for modifier in &self.0 {
+            modifier.apply(rng, particle, delta_time);
+        }
+    }
+
+    fn apply_to(&self, mut particles: ParticleSliceMut<'_>, rng: &mut SmallRng, delta_time: f32) {
+        for mut particle in particles.iter_mut() {
+            self.apply_to_particle(rng, &mut particle, delta_time);
+        }
+    }
+}
+
+#[cfg(feature = "inspector")]
+impl bevy_inspector_egui::Inspectable for StackedModifier {
+    type Attributes = ();
+
+    fn ui(
+        &mut self,
+        ui: &mut bevy_inspector_egui::egui::Ui,
+        _options: Self::Attributes,
+        context: &mut bevy_inspector_egui::Context,
+    ) -> bool {
+        macro_rules! modifier_ui {
+            ($($variant:ident),*) => {
+                match self {
+                    $(Self::$variant(m) => {
+                        ui.label(stringify!($variant));
+                        m.ui(ui, Default::default(), context)
+                    })*
+                    Self::Custom(m) => {
+                        ui.label(format!("{:?}", m));
+                        false
+                    }
+                }
+            };
+        }
+        modifier_ui!(
+            MaxParticleSize,
+            MaxParticleSpeed,
+            ParticleGravity,
+            SpeedOverTime,
+            SpeedOverLifetime,
+            VelocityOverTime,
+            AngularVelocityOverTime,
+            AngularVelocityOverTime3d,
+            OrbitalVelocityOverLifeTime,
+            LinearVelocityOverLifeTime,
+            SizeOverTime,
+            SizeOverSpeed,
+            SizeOverLifetime,
+            RotationOverVelocity,
+            RotationOverTime,
+            RotationOverTime3d,
+            RotationOverLifetime,
+            ColorOverLifeTime,
+            ColorOverSpeed,
+            AlphaOverLifetime,
+            PerlinNoise
+        )
+    }
+}
+
+#[cfg(feature = "inspector")]
+impl bevy_inspector_egui::Inspectable for ParticleModifiers {
+    type Attributes = ();
+
+    fn ui(
+        &mut self,
+        ui: &mut bevy_inspector_egui::egui::Ui,
+        _options: Self::Attributes,
+        context: &mut bevy_inspector_egui::Context,
+    ) -> bool {
+        let mut changed = false;
+        ui.vertical(|ui| {
+            for (i, modifier) in self.0.iter_mut().enumerate() {
+                if i > 0 {
+                    ui.separator();
+                }
+                changed |= modifier.ui(ui, (), &mut context.with_id(i as u64));
+            }
+        });
+        changed
+    }
+}
@@ -1,55 +1,71 @@
-use crate::modifiers::ParticleModifier;
-use crate::{Particle, RangeOrFixed};
+use crate::modifiers::{ParticleModifier, ParticleRngModifier};
+use crate::{Curve, ParticleMut, RangeOrFixed};
 use bevy::prelude::{Component, Reflect, Vec3};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// Increases particle speed over time
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct SpeedOverTime(pub f32);
 
 impl ParticleModifier for SpeedOverTime {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
-        particle.velocity += delta_time * self.0;
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        *particle.velocity += delta_time * self.0;
     }
 }
 
 /// Increases particle velocity over time
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct VelocityOverTime(pub Vec3);
 
 impl ParticleModifier for VelocityOverTime {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
-        particle.velocity += delta_time * self.0;
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        *particle.velocity += delta_time * self.0;
     }
 }
 
 /// Increases particle angular velocity over time
 ///
 /// Note: Will not work on particles set to align with their direction
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct AngularVelocityOverTime(pub f32);
 
 impl ParticleModifier for AngularVelocityOverTime {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
         particle.try_add_angular_velocity(delta_time * self.0);
     }
 }
 
+/// Increases particle 3D angular velocity over time
+///
+/// Note: Will not work on particles set to align with their direction, particles with a 1D rotation
+/// only use the `z` axis
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct AngularVelocityOverTime3d(pub Vec3);
+
+impl ParticleModifier for AngularVelocityOverTime3d {
+    fn apply(&self, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        particle.try_add_angular_velocity_3d(self.0 * delta_time);
+    }
+}
+
 /// Changes particle velocity over its lifetime
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct LinearVelocityOverLifeTime(RangeOrFixed<Vec3>);
 
-impl ParticleModifier for LinearVelocityOverLifeTime {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
-        particle.velocity += self.0.sample(particle.alive_time_ratio()) * delta_time;
+impl ParticleRngModifier for LinearVelocityOverLifeTime {
+    fn apply(&self, rng: &mut impl Rng, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        *particle.velocity += self.0.sample(particle.alive_time_ratio(), rng) * delta_time;
     }
 }
 
 /// Allows particle to orbit around 3D axis
-#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct OrbitalVelocityOverLifeTime {
     /// Orbital velocity of particles around the X, Y and Z axes.
@@ -60,11 +76,11 @@ pub struct OrbitalVelocityOverLifeTime {
     // pub radial: f32,
 }
 
-impl ParticleModifier for OrbitalVelocityOverLifeTime {
-    fn apply(&self, particle: &mut Particle, delta_time: f32) {
-        let values = self.velocity.sample(particle.alive_time_ratio());
+impl ParticleRngModifier for OrbitalVelocityOverLifeTime {
+    fn apply(&self, rng: &mut impl Rng, particle: &mut ParticleMut<'_>, delta_time: f32) {
+        let values = self.velocity.sample(particle.alive_time_ratio(), rng);
         let mut target_velocity = Vec3::ZERO;
-        let translation = particle.translation - self.offset;
+        let translation = *particle.translation - self.offset;
         if values.x.is_normal() {
             let (cos, sin) = (values.x.cos(), values.x.sin());
             target_velocity.y += translation.y * cos - translation.z * sin;
@@ -80,6 +96,25 @@ impl ParticleModifier for OrbitalVelocityOverLifeTime {
             target_velocity.x += translation.x * cos - translation.y * sin;
             target_velocity.y += translation.y.mul_add(sin, translation.y * cos);
         }
-        particle.velocity += target_velocity * delta_time;
+        *particle.velocity += target_velocity * delta_time;
+    }
+}
+
+/// **Sets** particle speed over its lifetime, using a curve evaluated on the particle alive time
+/// ratio. The particle direction is preserved
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct SpeedOverLifetime(pub Curve);
+
+impl ParticleModifier for SpeedOverLifetime {
+    fn apply(&self, particle: &mut ParticleMut<'_>, _delta_time: f32) {
+        let speed = self.0.evaluate(particle.alive_time_ratio());
+        *particle.velocity = particle.non_zero_direction() * speed;
+    }
+}
+
+impl From<Curve> for SpeedOverLifetime {
+    fn from(curve: Curve) -> Self {
+        Self(curve)
     }
 }
@@ -1,6 +1,7 @@
 use crate::Shape;
 use bevy::prelude::{Reflect, Vec3};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 pub mod shape_enum;
@@ -8,7 +9,7 @@ pub mod shape_enum;
 pub mod shapes;
 
 /// Defines the direction of the particles after emission
-#[derive(Debug, Copy, Clone, Reflect)]
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct EmitterDirectionParams {
     /// Base direction mode
@@ -22,7 +23,7 @@ pub struct EmitterDirectionParams {
 }
 
 /// Different emission spread loop modes
-#[derive(Debug, Copy, Clone, Reflect)]
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum SpreadLoopMode {
     /// loops back to the start at the end of each cycle
@@ -32,7 +33,7 @@ pub enum SpreadLoopMode {
 }
 
 /// Spread parameters for one axis
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct AxisSpread {
     /// Amount of spread in each direction:
@@ -49,7 +50,8 @@ pub struct AxisSpread {
 }
 
 /// Defines [`EmissionMode::Spread`] parameters
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct EmissionSpread {
     /// Spread parameters for each axis
@@ -58,14 +60,16 @@ pub struct EmissionSpread {
     pub spreads: [AxisSpread; 3],
     #[doc(hidden)]
     #[cfg_attr(feature = "inspector", inspectable(read_only))]
+    #[serde(skip)]
     pub current_index: Vec3,
     #[doc(hidden)]
     #[cfg_attr(feature = "inspector", inspectable(read_only))]
+    #[serde(skip)]
     pub upwards: [bool; 3],
 }
 
 /// Emission modes
-#[derive(Debug, Copy, Clone, Reflect)]
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum EmissionMode {
     /// Default mode, particles are placed randomly in the volume
@@ -75,7 +79,7 @@ pub enum EmissionMode {
 }
 
 /// Defines the direction of the particles after emission
-#[derive(Debug, Copy, Clone, Reflect)]
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum EmitterDirectionMode {
     /// default, The direction is taken from the shape
@@ -108,7 +112,7 @@ pub trait Emitter: Debug + Clone {
 }
 
 /// Defines the particle emission volume and various emission option
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct EmitterShape {
     /// The emission shape
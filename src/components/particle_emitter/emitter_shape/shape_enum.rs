@@ -2,9 +2,10 @@ use super::{EmittedParticle, Emitter, EmitterDirectionMode};
 use crate::{shapes, EmissionSpread};
 use bevy::prelude::Reflect;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// Available shapes for the particle emitter
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum Shape {
     /// Initializes particles at randomly-sampled positions within a sphere and directs them outwards from the center
@@ -2,6 +2,7 @@ use crate::components::particle_emitter::emitter_shape::{EmittedParticle, Emitte
 use crate::{line_spread, random_in_line, EmissionSpread, EmitterDirectionMode};
 use bevy::prelude::Vec3;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// Initializes particles at randomly-sampled positions within a box and directs them out of one of the six box faces.
 ///
@@ -16,7 +17,7 @@ use rand::Rng;
 ///
 /// * The `thickness` is not yet supported
 /// * Non `uniform` spread is not available
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct Box {
     /// Box half extents
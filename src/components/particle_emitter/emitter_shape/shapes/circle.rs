@@ -3,6 +3,7 @@ use crate::shapes::PI_2;
 use crate::{radius_spread, random_in_radius, EmissionSpread, EmitterDirectionMode};
 use bevy::prelude::Vec3;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// Initializes particles at randomly-sampled positions within a circle in the direction of the emitter’s up axis
 ///
@@ -12,7 +13,7 @@ use rand::Rng;
 /// * `x` - Not used
 /// * `y` - rotation spread
 /// * `z` - radius spread
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct Circle {
     /// Circle radius
@@ -3,6 +3,7 @@ use crate::shapes::PI_2;
 use crate::{EmissionSpread, EmitterDirectionMode};
 use bevy::prelude::Vec3;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// Initializes particles at the tip of a cone and directs them at random angles out of the cone.
 /// The cone is oriented along the up axis of the emitter.
@@ -17,7 +18,7 @@ use rand::Rng;
 /// ### Missing Spread features:
 ///
 /// * Non `uniform` spread is not available
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct Cone {
     /// The cone angle, between `0` and `1` representing between 0 and 90 degrees
@@ -1,11 +1,15 @@
 use crate::components::particle_emitter::emitter_shape::{EmittedParticle, Emitter};
+use crate::utilities::{mesh_triangles, random_in_triangle};
 use crate::{radius_spread, EmissionSpread, EmitterDirectionMode};
 use bevy::prelude::{shape::Cube, Mesh, Vec3};
 use bevy::render::mesh::VertexAttributeValues;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// Initializes particles at randomly-sampled positions within a convex mesh and directs them outwards from the `nominal_center`
 ///
+/// The random positions are picked on the mesh triangles, or on its vertices if it has no triangles
+///
 ///
 /// ## Spread
 ///
@@ -18,17 +22,17 @@ use rand::Rng;
 ///
 /// * The `thickness` is not yet supported
 /// * Non `uniform` spread is not available
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct ConvexMesh {
     /// The mesh object
+    #[serde(with = "crate::utilities::serde_mesh")]
     pub mesh: Mesh,
     /// The *nominal center* of the convex mesh
     pub nominal_center: Vec3,
 }
 
 impl Emitter for ConvexMesh {
-    // TODO: use triangles ?
     fn emit_random_particle(
         &self,
         rng: &mut impl Rng,
@@ -39,26 +43,21 @@ impl Emitter for ConvexMesh {
         if mesh.count_vertices() == 0 {
             return Default::default();
         }
-        let positions = mesh
-            .attribute(Mesh::ATTRIBUTE_POSITION)
-            .expect("No vertex positions set for `ConvexMesh`");
-        // TODO: use triangles
-        //  let indices = mesh.indices().expect("No indices set for `ConvexMesh");
-        //         let iterator = match indices {
-        //             Indices::U16(vec) => vec.iter().map(|i| i as usize),
-        //             Indices::U32(vec) => vec.iter().map(|i| i as usize),
-        //         };
-        //         let triangles: Vec<[usize; 3]> = iterator.chunks(3).collect();
-        //         let triangle = triangles[rng.gen_range(0..triangles.len())];
-        //         let positions = if let VertexAttributeValues::Float32x3(positions) = positions {
-        //             [positions[triangle[0]],positions[triangle[1]],positions[triangle[2]]]
-        //         } else {
-        //             panic!("Expected a mesh with `Float32x3` positions");
-        //         };
-        let position: Vec3 = if let VertexAttributeValues::Float32x3(positions) = positions {
-            positions[rng.gen_range(0..positions.len())].into()
-        } else {
-            panic!("Expected a mesh with `Float32x3` positions");
+        let position = match mesh_triangles(mesh) {
+            Some(triangles) if !triangles.is_empty() => {
+                random_in_triangle(triangles[rng.gen_range(0..triangles.len())], rng)
+            }
+            // Meshes without triangles emit from their vertices
+            _ => {
+                let positions = mesh
+                    .attribute(Mesh::ATTRIBUTE_POSITION)
+                    .expect("No vertex positions set for `ConvexMesh`");
+                if let VertexAttributeValues::Float32x3(positions) = positions {
+                    positions[rng.gen_range(0..positions.len())].into()
+                } else {
+                    panic!("Expected a mesh with `Float32x3` positions");
+                }
+            }
         };
         let coef = rng.gen_range((1.0 - thickness)..=1.0);
         EmittedParticle {
@@ -2,6 +2,7 @@ use crate::components::particle_emitter::emitter_shape::{EmittedParticle, Emitte
 use crate::{line_spread, random_in_line, EmissionSpread, EmitterDirectionMode};
 use bevy::prelude::Vec3;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// Emit particles from a line segment. The particles move in the emitter object’s upward (Y) direction.
 ///
@@ -15,7 +16,7 @@ use rand::Rng;
 /// ### Missing Spread features:
 ///
 /// * Non `uniform` spread is not available
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct Edge {
     /// The edge length
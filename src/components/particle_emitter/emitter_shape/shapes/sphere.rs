@@ -3,6 +3,7 @@ use crate::shapes::PI_2;
 use crate::{radius_spread, random_in_radius, EmissionSpread, EmitterDirectionMode};
 use bevy::prelude::Vec3;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
 /// Initializes particles at randomly-sampled positions within a sphere and directs them outwards from the center
@@ -13,7 +14,7 @@ use std::f32::consts::PI;
 /// * `x` - rotation spread (phi)
 /// * `y` - rotation spread (theta)
 /// * `z` - radius spread
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct Sphere {
     /// Sphere radius
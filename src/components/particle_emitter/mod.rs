@@ -6,10 +6,11 @@ use bevy::prelude::{Component, Reflect, Transform, Vec3};
 use bevy::reflect::FromReflect;
 use emitter_shape::{EmittedParticle, EmitterShape};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 /// Describes a single Particle emitter burst
-#[derive(Debug, Default, Clone, Reflect)]
+#[derive(Debug, Default, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct Burst {
     /// Time after the start of the emission
@@ -19,7 +20,7 @@ pub struct Burst {
 }
 
 /// Duration of the particle emitter
-#[derive(Debug, Copy, Clone, Reflect)]
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum EmitterDuration {
     /// No duration limit
@@ -33,24 +34,46 @@ pub enum EmitterDuration {
     },
 }
 
+/// Emission state changes of a [`ParticleEmitter`] during a single emission
+#[derive(Debug, Clone, Default)]
+pub struct EmissionReport {
+    /// The emission (re)started
+    pub started: bool,
+    /// Indexes of the fired [`ParticleEmitter::bursts`]
+    pub fired_bursts: Vec<usize>,
+    /// The non looping emission duration elapsed
+    pub finished: bool,
+}
+
 /// Emitter of particles, works with [`ParticleSystem`]
-#[derive(Debug, Clone, Component, Reflect)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
 #[reflect(Component)]
+#[serde(default)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct ParticleEmitter {
     /// Emitter duration
     pub duration: EmitterDuration,
     /// The shape of the emitter
     pub shape: EmitterShape,
-    /// The rate of particle emission over time (`1.0` means 1 particle per second)
-    pub rate: f32,
+    /// The rate of particle emission over time (`1.0` means 1 particle per second), evaluated over
+    /// the emitter cycle
+    pub rate: RangeOrFixed<f32>,
     /// Custom bursts of particle emission
     pub bursts: Vec<Burst>,
+    /// If enabled, a full emission cycle is simulated when the particle system starts so it
+    /// appears fully developed.
+    ///
+    /// Only valid for looping [`EmitterDuration::FixedDuration`], requires a
+    /// [`ParticlePlayback`](crate::ParticlePlayback) component
+    pub prewarm: bool,
     /// time since first tick
+    #[serde(skip)]
     pub current_delta_time: f32,
-    /// Time since last `rate` emission
-    pub last_emitted_delta_time: f32,
+    /// Fractional amount of particles owed by the `rate` emission, integrated over time
+    #[serde(skip)]
+    pub pending_emission: f32,
     /// The shape transform
+    #[serde(with = "crate::utilities::serde_transform")]
     pub transform: Transform,
 }
 
@@ -71,49 +94,110 @@ impl Default for ParticleEmitter {
         Self {
             duration: Default::default(),
             shape: Default::default(),
-            rate: 5.0,
+            rate: RangeOrFixed::Fixed(5.0),
             bursts: vec![],
+            prewarm: false,
             current_delta_time: 0.0,
-            last_emitted_delta_time: 0.0,
+            pending_emission: 0.0,
             transform: Default::default(),
         }
     }
 }
 
 impl ParticleEmitter {
+    /// Resets the emission timing, restarting the emitter duration and bursts
+    pub const fn reset(&mut self) {
+        self.current_delta_time = 0.0;
+        self.pending_emission = 0.0;
+    }
+
+    /// Retrieves the duration to simulate to prewarm the emitter, if [`Self::prewarm`] is enabled
+    /// with a looping [`EmitterDuration::FixedDuration`]
+    #[must_use]
+    pub const fn prewarm_duration(&self) -> Option<f32> {
+        match self.duration {
+            EmitterDuration::FixedDuration {
+                duration,
+                looping: true,
+            } if self.prewarm => Some(duration),
+            _ => None,
+        }
+    }
+
+    /// Current progress of the emission cycle, between `0` and `1`, used to evaluate the curves of
+    /// the emission parameters. Always `0` for an [`EmitterDuration::Infinite`] emitter
+    #[must_use]
+    pub fn cycle_ratio(&self) -> f32 {
+        match self.duration {
+            EmitterDuration::FixedDuration { duration, .. } if duration > 0.0 => {
+                (self.current_delta_time / duration).clamp(0.0, 1.0)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Checks if the emitter has exhausted its non looping [`EmitterDuration::FixedDuration`]
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        match self.duration {
+            EmitterDuration::FixedDuration {
+                duration,
+                looping: false,
+            } => self.current_delta_time > duration,
+            _ => false,
+        }
+    }
+
     /// Computes particles to emit
     pub fn emit(&mut self, delta_time: f32, rng: &mut impl Rng) -> Vec<EmittedParticle> {
+        self.emit_with_report(delta_time, rng).0
+    }
+
+    /// Computes particles to emit, and reports the emission state changes
+    pub(crate) fn emit_with_report(
+        &mut self,
+        delta_time: f32,
+        rng: &mut impl Rng,
+    ) -> (Vec<EmittedParticle>, EmissionReport) {
+        let mut report = EmissionReport::default();
         // Check duration
         if let EmitterDuration::FixedDuration { duration, looping } = self.duration {
             if self.current_delta_time > duration {
                 if looping {
-                    self.current_delta_time = 0.0;
-                    self.last_emitted_delta_time = 0.0;
+                    self.reset();
                 }
-                return vec![];
+                return (vec![], report);
             }
         }
+        report.started = self.current_delta_time <= 0.0;
+        let cycle_ratio = self.cycle_ratio();
         // bursts
-        let mut emission_count = self
-            .bursts
-            .iter()
-            .filter(|b| {
-                b.time >= self.current_delta_time && b.time < self.current_delta_time + delta_time
-            })
-            .map(|b| b.count.evaluate_rng(rng))
-            .sum();
-        self.current_delta_time += delta_time;
-        // emission over time
-        if self.rate > 0.0 {
-            let delta_per_particle = 1.0 / self.rate;
-            let delay_since_emission = self.current_delta_time - self.last_emitted_delta_time;
-            let particles_to_emit = (delay_since_emission / delta_per_particle) as usize;
-            emission_count += particles_to_emit;
-            self.last_emitted_delta_time += delta_per_particle * particles_to_emit as f32;
+        let mut emission_count = 0;
+        for (index, burst) in self.bursts.iter().enumerate() {
+            if burst.time >= self.current_delta_time
+                && burst.time < self.current_delta_time + delta_time
+            {
+                emission_count += burst.count.evaluate(cycle_ratio, rng);
+                report.fired_bursts.push(index);
+            }
         }
+        self.current_delta_time += delta_time;
+        report.finished = self.is_finished();
+        // emission over time, the rate being integrated so it can change between steps
+        let rate = self.rate.evaluate(cycle_ratio, rng);
+        self.pending_emission += rate.max(0.0) * delta_time;
+        let particles_to_emit = self.pending_emission as usize;
+        emission_count += particles_to_emit;
+        self.pending_emission -= particles_to_emit as f32;
 
+        (self.emit_count(emission_count, rng), report)
+    }
+
+    /// Computes `count` particles to emit from the emitter shape, ignoring the emission rate,
+    /// bursts and duration
+    pub(crate) fn emit_count(&mut self, count: usize, rng: &mut impl Rng) -> Vec<EmittedParticle> {
         let matrix = self.transform.compute_matrix();
-        (0..emission_count)
+        (0..count)
             .map(|_| {
                 let mut particle = self.shape.emit_particle(rng);
                 particle.position = matrix.transform_point3(particle.position);
@@ -124,3 +208,55 @@ impl ParticleEmitter {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Curve, Keyframe};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    /// Emitter with a one second cycle and a `rate` of `multiplier` times `curve`
+    fn emitter(curve: Curve, multiplier: f32) -> ParticleEmitter {
+        ParticleEmitter {
+            duration: EmitterDuration::FixedDuration {
+                duration: 1.0,
+                looping: false,
+            },
+            rate: RangeOrFixed::Curve { curve, multiplier },
+            ..Default::default()
+        }
+    }
+
+    /// Amount of particles emitted on each of the 60 steps of the cycle
+    fn emitted_counts(mut emitter: ParticleEmitter) -> Vec<usize> {
+        let mut rng = SmallRng::seed_from_u64(0);
+        (0..60)
+            .map(|_| emitter.emit(STEP, &mut rng).len())
+            .collect()
+    }
+
+    #[test]
+    fn rate_ramp_from_zero_is_integrated() {
+        let counts = emitted_counts(emitter(Curve::linear(0.0, 1.0), 120.0));
+        assert_eq!(counts[0], 0);
+        // At most 120 particles per second, so 2 per step and one more from the remainder
+        assert!(counts.iter().all(|count| *count <= 3));
+        // The integral of the ramp, evaluated at the start of every step
+        let total: usize = counts.iter().sum();
+        assert!((58..=60).contains(&total), "{total}");
+    }
+
+    #[test]
+    fn zero_rate_does_not_accumulate() {
+        let curve = Curve::from(vec![Keyframe::step(0.0, 0.0), Keyframe::step(0.5, 1.0)]);
+        let counts = emitted_counts(emitter(curve, 60.0));
+        assert!(counts[..30].iter().all(|count| *count == 0));
+        // One particle per step once the rate rises, not the ones owed since the start
+        assert!(counts[30..].iter().all(|count| *count <= 1));
+        let total: usize = counts.iter().sum();
+        assert!((29..=30).contains(&total), "{total}");
+    }
+}
@@ -3,9 +3,10 @@ use bevy::ecs::reflect::ReflectComponent;
 use bevy::prelude::{Component, Handle, Image, Reflect, TextureAtlas};
 use bevy::render::texture::DEFAULT_IMAGE_HANDLE;
 use bevy::sprite::Rect;
+use serde::{Deserialize, Serialize};
 
 /// Defines the looping behaviour of the animated sheet
-#[derive(Debug, Copy, Clone, Reflect)]
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum TextureSheetLoopingMode {
     /// The texture sheet is not animated
@@ -22,7 +23,7 @@ pub enum TextureSheetLoopingMode {
 }
 
 /// Animation params for particle texture sheets
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct TextureSheetAnimation {
     /// Start cell (usually 0)
@@ -36,7 +37,7 @@ pub struct TextureSheetAnimation {
 }
 
 /// Texture Sheet resolve mode for particle systems
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum TextureSheetMode {
     /// Use a single cell of the texture sheet
@@ -62,17 +63,51 @@ pub struct ParticleTextureSheet {
     pub mode: TextureSheetMode,
 }
 
-/// The material of the particle, can be a texture or an animated texture sheet
-#[derive(Debug, Clone, Component, Reflect)]
-#[reflect(Component)]
+/// The texture of the particle, can be an image or an animated texture sheet
+#[derive(Debug, Clone, Reflect)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
-pub enum ParticleMaterial {
-    /// Single image material
+pub enum ParticleTexture {
+    /// Single image texture
     Image(Handle<Image>),
-    /// Texture sheet material
+    /// Texture sheet texture
     TextureSheet(ParticleTextureSheet),
 }
 
+/// Defines how the particles are blended with what is behind them
+#[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub enum ParticleBlendMode {
+    /// Standard alpha blending
+    #[default]
+    Alpha,
+    /// Alpha blending of premultiplied alpha textures, the particle color is premultiplied in the
+    /// shader
+    Premultiplied,
+    /// The particle color is added to the background, weighted by its alpha. Suits fire, sparks or
+    /// magic effects
+    Additive,
+    /// The background is multiplied by the particle color, weighted by its alpha
+    Multiply,
+    /// The particles are opaque and write to the depth buffer, the fragments with an alpha below
+    /// the [`ParticleMaterial::alpha_cutoff`] being discarded. They are drawn in the alpha mask
+    /// phase, before the transparent particles
+    Opaque,
+}
+
+/// The material of the particle: its texture and how it is blended
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct ParticleMaterial {
+    /// The particle texture
+    pub texture: ParticleTexture,
+    /// The particle blend mode
+    pub blend_mode: ParticleBlendMode,
+    /// Alpha threshold below which the fragments are discarded, only used in
+    /// [`ParticleBlendMode::Opaque`] mode
+    pub alpha_cutoff: f32,
+}
+
 impl Default for TextureSheetAnimation {
     fn default() -> Self {
         Self {
@@ -89,21 +124,58 @@ impl Default for TextureSheetMode {
     }
 }
 
-impl Default for ParticleMaterial {
+impl Default for ParticleTexture {
     fn default() -> Self {
         Self::Image(DEFAULT_IMAGE_HANDLE.typed())
     }
 }
 
+impl Default for ParticleMaterial {
+    fn default() -> Self {
+        Self {
+            texture: ParticleTexture::default(),
+            blend_mode: ParticleBlendMode::default(),
+            alpha_cutoff: 0.5,
+        }
+    }
+}
+
+impl ParticleMaterial {
+    /// Alpha blended single image material
+    #[must_use]
+    pub fn image(image: Handle<Image>) -> Self {
+        Self {
+            texture: ParticleTexture::Image(image),
+            ..Default::default()
+        }
+    }
+
+    /// Alpha blended texture sheet material
+    #[must_use]
+    pub fn texture_sheet(sheet: ParticleTextureSheet) -> Self {
+        Self {
+            texture: ParticleTexture::TextureSheet(sheet),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the material blend mode
+    #[must_use]
+    pub const fn with_blend_mode(mut self, blend_mode: ParticleBlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+}
+
 impl From<Handle<Image>> for ParticleMaterial {
     fn from(handle: Handle<Image>) -> Self {
-        Self::Image(handle)
+        Self::image(handle)
     }
 }
 
 impl From<ParticleTextureSheet> for ParticleMaterial {
     fn from(sheet: ParticleTextureSheet) -> Self {
-        Self::TextureSheet(sheet)
+        Self::texture_sheet(sheet)
     }
 }
 
@@ -1,11 +1,13 @@
 use crate::particle::{Particle, ParticleRotation};
 use crate::{ColorOrGradient, RangeOrFixed};
 use bevy::ecs::reflect::ReflectComponent;
-use bevy::prelude::{Component, Reflect, Vec3};
+use bevy::math::EulerRot;
+use bevy::prelude::{Component, Quat, Reflect, Vec3, Vec4};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-/// Defines the `z` rotation behaviour of particles
-#[derive(Debug, Clone, Reflect)]
+/// Defines the rotation behaviour of particles
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum RotationMode {
     /// The particle rotation will always align to its current direction.
@@ -22,23 +24,58 @@ pub enum RotationMode {
         /// Start angular velocity for simulated particles
         start_angular_velocity: RangeOrFixed<f32>,
     },
+    /// The rotation is free on every axis, for mesh particles or billboards aligned with the world
+    /// or local axes. Camera facing billboards only use the `z` rotation
+    FreeRotation3d {
+        /// Start rotation for simulated particles
+        start_rotation: StartRotation3d,
+        /// Start angular velocity for simulated particles, in radians per second around the
+        /// particle local axes
+        start_angular_velocity: RangeOrFixed<Vec3>,
+    },
+}
+
+/// Defines the start 3D rotation of particles
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub enum StartRotation3d {
+    /// Euler angles in radians, applied in the `X`, `Y`, `Z` order
+    Euler(RangeOrFixed<Vec3>),
+    /// Uniformly distributed random rotation
+    Random,
+}
+
+/// Defines the start size of particles
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub enum StartSize {
+    /// The same size is used on every axis
+    Uniform(RangeOrFixed<f32>),
+    /// Separate size on each axis, billboards only use `x` and `y`
+    PerAxis(RangeOrFixed<Vec3>),
 }
 
 /// Defines the initial state of emitted particles
-#[derive(Debug, Clone, Component, Reflect)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
 #[reflect(Component)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct ParticleParams {
     /// Start lifetime of simulated particles
     pub start_lifetime: RangeOrFixed<f32>,
     /// Start size of simulated particles
-    pub start_size: RangeOrFixed<f32>,
+    pub start_size: StartSize,
     /// rotation mode for simulated particles
     pub rotation: RotationMode,
     /// Start speed for simulated particles
     pub start_speed: RangeOrFixed<f32>,
     /// Start color for simulated particles
     pub start_color: ColorOrGradient,
+    /// Start value of the first custom data slot of simulated particles
+    #[serde(default)]
+    pub start_custom_1: RangeOrFixed<Vec4>,
+    /// Start value of the second custom data slot of simulated particles
+    #[serde(default)]
+    pub start_custom_2: RangeOrFixed<Vec4>,
 }
 
 impl ParticleParams {
@@ -48,38 +85,133 @@ impl ParticleParams {
     ///
     /// * `position` - The translation of the particle
     /// * `direction` - the direction of the particle
+    /// * `emitter_time` - the emitter cycle progress, used to evaluate the curve params (see
+    ///   [`ParticleEmitter::cycle_ratio`])
     /// * `rng`- random generator
+    ///
+    /// [`ParticleEmitter::cycle_ratio`]: crate::ParticleEmitter::cycle_ratio
     pub fn get_particle(
         &self,
         position: Vec3,
         start_direction: Vec3,
+        emitter_time: f32,
         rng: &mut impl Rng,
     ) -> Particle {
-        let lifetime = self.start_lifetime.evaluate_rng(rng);
+        let lifetime = self.start_lifetime.evaluate(emitter_time, rng);
+        let size = self.start_size.evaluate(emitter_time, rng);
         Particle {
             translation: position,
-            rotation: match self.rotation {
+            rotation: match &self.rotation {
                 RotationMode::AlignToDirection { offset } => {
-                    ParticleRotation::AlignToDirection { offset }
+                    ParticleRotation::AlignToDirection { offset: *offset }
                 }
                 RotationMode::FreeRotation {
                     start_rotation,
                     start_angular_velocity,
                 } => ParticleRotation::FreeRotation {
-                    rotation: start_rotation.evaluate_rng(rng),
-                    angular_velocity: start_angular_velocity.evaluate_rng(rng),
+                    rotation: start_rotation.evaluate(emitter_time, rng),
+                    angular_velocity: start_angular_velocity.evaluate(emitter_time, rng),
+                },
+                RotationMode::FreeRotation3d {
+                    start_rotation,
+                    start_angular_velocity,
+                } => ParticleRotation::FreeRotation3d {
+                    rotation: start_rotation.evaluate(emitter_time, rng),
+                    angular_velocity: start_angular_velocity.evaluate(emitter_time, rng),
                 },
             },
-            size: self.start_size.evaluate_rng(rng),
+            size,
+            start_size: size,
             lifetime,
             start_lifetime: lifetime,
             color: self.start_color.evaluate(rng.gen_range(0.0..=1.0)),
-            velocity: start_direction * self.start_speed.evaluate_rng(rng),
+            velocity: start_direction * self.start_speed.evaluate(emitter_time, rng),
             start_direction,
+            custom_1: self.start_custom_1.evaluate(emitter_time, rng),
+            custom_2: self.start_custom_2.evaluate(emitter_time, rng),
         }
     }
 }
 
+impl StartSize {
+    /// Evaluates the particle size at `time` using `rng`
+    #[must_use]
+    pub fn evaluate(&self, time: f32, rng: &mut impl Rng) -> Vec3 {
+        match self {
+            Self::Uniform(size) => Vec3::splat(size.evaluate(time, rng)),
+            Self::PerAxis(size) => size.evaluate(time, rng),
+        }
+    }
+}
+
+impl StartRotation3d {
+    /// Evaluates the particle rotation at `time` using `rng`
+    #[must_use]
+    pub fn evaluate(&self, time: f32, rng: &mut impl Rng) -> Quat {
+        match self {
+            Self::Euler(angles) => {
+                let angles = angles.evaluate(time, rng);
+                Quat::from_euler(EulerRot::XYZ, angles.x, angles.y, angles.z)
+            }
+            Self::Random => {
+                // Uniform random unit quaternion (Shoemake)
+                let (u1, u2, u3): (f32, f32, f32) = (rng.gen(), rng.gen(), rng.gen());
+                let (a, b) = ((1.0 - u1).sqrt(), u1.sqrt());
+                let (u2, u3) = (u2 * std::f32::consts::TAU, u3 * std::f32::consts::TAU);
+                Quat::from_xyzw(a * u2.sin(), a * u2.cos(), b * u3.sin(), b * u3.cos())
+            }
+        }
+    }
+}
+
+impl Default for StartRotation3d {
+    fn default() -> Self {
+        Self::Euler(RangeOrFixed::default())
+    }
+}
+
+impl From<Vec3> for StartRotation3d {
+    fn from(angles: Vec3) -> Self {
+        Self::Euler(angles.into())
+    }
+}
+
+impl From<RangeOrFixed<Vec3>> for StartRotation3d {
+    fn from(angles: RangeOrFixed<Vec3>) -> Self {
+        Self::Euler(angles)
+    }
+}
+
+impl Default for StartSize {
+    fn default() -> Self {
+        Self::Uniform(RangeOrFixed::Fixed(1.0))
+    }
+}
+
+impl From<f32> for StartSize {
+    fn from(size: f32) -> Self {
+        Self::Uniform(size.into())
+    }
+}
+
+impl From<RangeOrFixed<f32>> for StartSize {
+    fn from(size: RangeOrFixed<f32>) -> Self {
+        Self::Uniform(size)
+    }
+}
+
+impl From<Vec3> for StartSize {
+    fn from(size: Vec3) -> Self {
+        Self::PerAxis(size.into())
+    }
+}
+
+impl From<RangeOrFixed<Vec3>> for StartSize {
+    fn from(size: RangeOrFixed<Vec3>) -> Self {
+        Self::PerAxis(size)
+    }
+}
+
 impl Default for RotationMode {
     fn default() -> Self {
         Self::FreeRotation {
@@ -93,10 +225,12 @@ impl Default for ParticleParams {
     fn default() -> Self {
         Self {
             start_lifetime: RangeOrFixed::Fixed(5.0),
-            start_size: RangeOrFixed::Fixed(1.0),
+            start_size: StartSize::default(),
             rotation: RotationMode::default(),
             start_speed: RangeOrFixed::Fixed(1.0),
             start_color: ColorOrGradient::default(),
+            start_custom_1: RangeOrFixed::default(),
+            start_custom_2: RangeOrFixed::default(),
         }
     }
 }
@@ -0,0 +1,149 @@
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::{Component, Reflect};
+
+/// Playback state of a particle system
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub enum PlaybackState {
+    /// The particle system emits and simulates particles
+    #[default]
+    Playing,
+    /// The emission and the simulation are frozen
+    Paused,
+    /// The particle system no longer emits particles but its current particles are still
+    /// simulated
+    Stopped,
+}
+
+/// Controls the playback of a particle system: pausing, stopping, restarting or simulating
+/// ahead.
+///
+/// The requested changes are applied at the next particle simulation
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct ParticlePlayback {
+    /// Multiplier of the particle system delta time
+    pub time_scale: f32,
+    /// The current playback state
+    pub(crate) state: PlaybackState,
+    /// Requests the emitter timing to be reset
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) reset_emitter: bool,
+    /// Requests the particles to be cleared
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) clear_particles: bool,
+    /// Requests the emitter to be prewarmed, if enabled
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) prewarm: bool,
+    /// Duration to simulate ahead, in seconds of the particle system time
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) pending_simulation: f32,
+}
+
+impl Default for ParticlePlayback {
+    fn default() -> Self {
+        Self {
+            time_scale: 1.0,
+            state: PlaybackState::default(),
+            reset_emitter: false,
+            clear_particles: false,
+            prewarm: true,
+            pending_simulation: 0.0,
+        }
+    }
+}
+
+impl ParticlePlayback {
+    /// The current playback state
+    #[must_use]
+    #[inline]
+    pub const fn state(&self) -> PlaybackState {
+        self.state
+    }
+
+    /// Is the particle system playing
+    #[must_use]
+    #[inline]
+    pub fn is_playing(&self) -> bool {
+        self.state == PlaybackState::Playing
+    }
+
+    /// Is the particle system paused
+    #[must_use]
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.state == PlaybackState::Paused
+    }
+
+    /// Is the particle system stopped
+    #[must_use]
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.state == PlaybackState::Stopped
+    }
+
+    /// Plays or resumes the particle system. A stopped particle system restarts its emission, and
+    /// is prewarmed if [`ParticleEmitter::prewarm`](crate::ParticleEmitter::prewarm) is enabled
+    pub fn play(&mut self) {
+        if self.is_stopped() {
+            self.reset_emitter = true;
+            self.prewarm = true;
+        }
+        self.state = PlaybackState::Playing;
+    }
+
+    /// Pauses the particle system, freezing both the emission and the simulation
+    pub fn pause(&mut self) {
+        if self.is_playing() {
+            self.state = PlaybackState::Paused;
+        }
+    }
+
+    /// Stops the particle system emission
+    ///
+    /// # Arguments
+    ///
+    /// * `clear` - If enabled, the current particles are removed, otherwise they keep being
+    ///   simulated until they die
+    pub const fn stop(&mut self, clear: bool) {
+        self.state = PlaybackState::Stopped;
+        self.clear_particles |= clear;
+    }
+
+    /// Restarts the particle system, clearing its particles and resetting its emission. The
+    /// particle system is prewarmed if [`ParticleEmitter::prewarm`](crate::ParticleEmitter::prewarm)
+    /// is enabled
+    pub const fn restart(&mut self) {
+        self.state = PlaybackState::Playing;
+        self.reset_emitter = true;
+        self.clear_particles = true;
+        self.prewarm = true;
+    }
+
+    /// Simulates the particle system `seconds` ahead, in fixed steps, even if it is paused. The
+    /// duration is scaled by [`Self::time_scale`], and non finite durations are ignored.
+    ///
+    /// Useful to pre-warm a particle system
+    pub fn simulate(&mut self, seconds: f32) {
+        self.simulate_scaled(seconds * self.time_scale.max(0.0));
+    }
+
+    /// Simulates the particle system `seconds` of its own time ahead, ignoring
+    /// [`Self::time_scale`]. Non finite durations are ignored
+    pub(crate) fn simulate_scaled(&mut self, seconds: f32) {
+        let pending_simulation = self.pending_simulation + seconds.max(0.0);
+        if pending_simulation.is_finite() {
+            self.pending_simulation = pending_simulation;
+        }
+    }
+
+    /// Computes the particle system delta time for a frame of `delta_time` seconds
+    pub(crate) fn scaled_delta_time(&self, delta_time: f32) -> f32 {
+        if self.is_paused() {
+            0.0
+        } else {
+            delta_time * self.time_scale.max(0.0)
+        }
+    }
+}
@@ -1,16 +1,17 @@
 use crate::{Particle, Vec3};
 use bevy::ecs::reflect::ReflectComponent;
-use bevy::prelude::{Component, GlobalTransform, Quat, Reflect, Transform};
+use bevy::prelude::{Component, GlobalTransform, Handle, Mat3, Mesh, Quat, Reflect, Transform};
+use serde::{Deserialize, Serialize};
 
 /// Defines how the particle billboard is aligned
-#[derive(Debug, Copy, Clone, Reflect)]
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum BillBoardAlignment {
     /// Particles face the Camera plane
     View,
-    /// Particles are aligned with the world axes
+    /// Particles are aligned with the world axes, and use their full 3D rotation
     World,
-    /// Particles are aligned to the local transform
+    /// Particles are aligned to the local transform, and use their full 3D rotation
     Local,
     /// Particles face the direct position of the Camera transform
     Facing,
@@ -19,7 +20,7 @@ pub enum BillBoardAlignment {
     Direction,
 }
 
-#[derive(Debug, Clone, Component, Reflect)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 #[reflect(Component)]
 /// Defines how the particle image is rendered
@@ -33,6 +34,27 @@ pub enum ParticleRenderMode {
     VerticalBillboard,
     /// The particle plane is parallel to the XZ “floor” plane.
     HorizontalBillBoard,
+    /// The particle faces the Camera and is stretched along its velocity, its length being
+    /// `size.y * length_scale + speed * speed_scale`.
+    ///
+    /// Note: The particle rotation is ignored
+    StretchedBillboard {
+        /// Length added to the particle per unit of speed
+        speed_scale: f32,
+        /// Multiplier of the particle length
+        length_scale: f32,
+        /// Amount of the Camera velocity subtracted from the particles velocity, `1.0` meaning the
+        /// particles are stretched along their velocity relative to the Camera
+        camera_velocity_scale: f32,
+    },
+    /// The particles render as instances of a 3D mesh, rotated by the particle 3D rotation and scaled
+    /// by the particle size. Local space particles are also rotated by the particle system. The
+    /// particle material texture is mapped using the mesh UVs.
+    ///
+    /// Note: This mode can't be serialized, particle effect assets reference the mesh by its asset
+    /// path (see [`ParticleEffectRenderMode`](crate::prelude::ParticleEffectRenderMode))
+    #[serde(skip)]
+    Mesh(Handle<Mesh>),
 }
 
 impl Default for BillBoardAlignment {
@@ -71,13 +93,33 @@ impl ParticleRenderMode {
         Self::BillBoard { alignment }
     }
 
+    #[inline]
+    #[must_use]
+    /// [`Self::StretchedBillboard`], ignoring the Camera velocity
+    pub const fn stretched(speed_scale: f32, length_scale: f32) -> Self {
+        Self::StretchedBillboard {
+            speed_scale,
+            length_scale,
+            camera_velocity_scale: 0.0,
+        }
+    }
+
     pub(crate) fn apply_to_particle(
         &self,
         particle: &Particle,
         transform: &mut Transform,
         camera_transform: &GlobalTransform,
+        camera_velocity: Vec3,
+        world_space: bool,
     ) {
         match self {
+            Self::Mesh(_) => {
+                // Local space mesh particles keep the particle system rotation
+                if world_space {
+                    transform.rotation = Quat::IDENTITY;
+                }
+                transform.scale = particle.size;
+            }
             ParticleRenderMode::HorizontalBillBoard => {
                 transform.rotation = Quat::from_rotation_x(1.5);
             }
@@ -85,6 +127,21 @@ impl ParticleRenderMode {
                 let delta = transform.translation - camera_transform.translation;
                 transform.rotation = Quat::from_rotation_y(delta.x.atan2(delta.z));
             }
+            Self::StretchedBillboard {
+                speed_scale,
+                length_scale,
+                camera_velocity_scale,
+            } => {
+                let velocity = particle.velocity - camera_velocity * *camera_velocity_scale;
+                Self::stretch(
+                    velocity,
+                    particle.size.y,
+                    (*speed_scale, *length_scale),
+                    transform,
+                    camera_transform,
+                );
+                return;
+            }
             ParticleRenderMode::BillBoard { alignment } => match alignment {
                 BillBoardAlignment::View => {
                     transform.rotation = -camera_transform.rotation;
@@ -104,6 +161,33 @@ impl ParticleRenderMode {
                 }
             },
         };
-        transform.rotation *= Quat::from_rotation_z(particle.rotation());
+        transform.rotation *= match self {
+            Self::Mesh(_)
+            | Self::BillBoard {
+                alignment: BillBoardAlignment::World | BillBoardAlignment::Local,
+            } => particle.rotation_3d(),
+            _ => Quat::from_rotation_z(particle.rotation()),
+        };
+    }
+
+    /// Faces the Camera plane with the particle `y` axis aligned to its `velocity` projected on the
+    /// Camera plane, and scales the particle along its `y` axis
+    fn stretch(
+        velocity: Vec3,
+        size: f32,
+        (speed_scale, length_scale): (f32, f32),
+        transform: &mut Transform,
+        camera_transform: &GlobalTransform,
+    ) {
+        if size > 0.0 {
+            transform.scale.y = velocity.length().mul_add(speed_scale / size, length_scale);
+        }
+        let normal = camera_transform.local_z();
+        let projected = velocity - normal * velocity.dot(normal);
+        transform.rotation = projected
+            .try_normalize()
+            .map_or(camera_transform.rotation, |up| {
+                Quat::from_mat3(&Mat3::from_cols(up.cross(normal), up, normal))
+            });
     }
 }
@@ -2,7 +2,10 @@ use bevy::prelude::Component;
 use rand::rngs::SmallRng;
 use rand::SeedableRng;
 
-/// Component responsible for the randomization of particles
+/// Component responsible for the randomization of particles.
+///
+/// Use [`ParticleRng::from_seed`] for reproducible particle systems: the same seed simulated with
+/// the same steps (see [`ParticleTimeStep`](crate::ParticleTimeStep)) produces identical particles
 #[derive(Debug, Clone, Component)]
 pub struct ParticleRng(pub SmallRng);
 
@@ -13,6 +16,12 @@ impl Default for ParticleRng {
 }
 
 impl ParticleRng {
+    /// Creates a randomizer from a `seed`
+    #[must_use]
+    pub fn from_seed(seed: u64) -> Self {
+        Self(SmallRng::seed_from_u64(seed))
+    }
+
     /// Retrieves the random generator
     pub fn rng(&mut self) -> &mut SmallRng {
         &mut self.0
@@ -1,8 +1,10 @@
+use crate::particle::ParticleStorage;
+use crate::utilities::parallel;
 use crate::Particle;
 use bevy::ecs::reflect::ReflectComponent;
 use bevy::prelude::{Component, GlobalTransform, Reflect, Vec3};
 use bevy::render::primitives::Aabb;
-use itertools::{Itertools, MinMaxResult};
+use bevy::tasks::TaskPool;
 use std::ops::Deref;
 
 /// Particle System simulation container
@@ -14,11 +16,28 @@ pub struct ParticleSystem {
     pub world_space: bool,
     /// Every simulated particle
     #[cfg_attr(feature = "inspector", inspectable(ignore))]
-    pub(crate) particles: Vec<Particle>,
+    #[reflect(ignore)]
+    pub(crate) particles: ParticleStorage,
+    /// Particles born during the last emission
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) born_particles: Vec<Particle>,
+    /// Particles which died during the last update
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) dead_particles: Vec<Particle>,
+    /// Particles which collided during the last update
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) collided_particles: Vec<Particle>,
+    /// Delta time of the current simulation step
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) delta_time: f32,
+    /// Did the particle system become empty since the last
+    /// [`ParticleSystemEmpty`](crate::ParticleSystemEmpty) event
+    #[cfg_attr(feature = "inspector", inspectable(ignore))]
+    pub(crate) emptied: bool,
 }
 
 impl Deref for ParticleSystem {
-    type Target = Vec<Particle>;
+    type Target = ParticleStorage;
 
     fn deref(&self) -> &Self::Target {
         &self.particles
@@ -26,44 +45,126 @@ impl Deref for ParticleSystem {
 }
 
 impl ParticleSystem {
-    // TODO: Benchmark this and try with `retain_mut` equivalent
-    pub(crate) fn update(&mut self, delta_time: f32) {
-        for particle in &mut self.particles {
-            particle.update(delta_time);
-        }
-        self.particles.retain(|particle| particle.lifetime > 0.);
+    pub(crate) fn update(&mut self, delta_time: f32, pool: &TaskPool) {
+        self.dead_particles.clear();
+        parallel::for_each_chunk_mut(pool, self.particles.as_slice_mut(), |mut particles| {
+            for mut particle in particles.iter_mut() {
+                particle.update(delta_time);
+            }
+        });
+        self.particles.remove_dead(&mut self.dead_particles);
+        self.emptied |= !self.dead_particles.is_empty() && self.particles.is_empty();
+    }
+
+    /// Starts a new simulation step of `delta_time` seconds, required to run the
+    /// [`ParticleSimulation`](crate::ParticleSimulation) stage manually
+    pub fn begin_step(&mut self, delta_time: f32) {
+        self.delta_time = delta_time;
+        self.born_particles.clear();
+        self.dead_particles.clear();
+        self.collided_particles.clear();
+    }
+
+    /// Delta time of the current simulation step, `0` if the particle system is not simulated
+    /// (paused for example)
+    #[must_use]
+    pub const fn delta_time(&self) -> f32 {
+        self.delta_time
+    }
+
+    /// Is the particle system simulated during the current step
+    #[must_use]
+    pub(crate) fn is_simulated(&self) -> bool {
+        self.delta_time > 0.0
+    }
+
+    /// Removes every particle from the system
+    pub fn clear(&mut self) {
+        self.emptied |= !self.particles.is_empty();
+        self.particles.clear();
+    }
+
+    /// Removes the `count` oldest particles, adding them to the dead particles
+    pub(crate) fn remove_oldest(&mut self, count: usize) {
+        let had_particles = !self.particles.is_empty();
+        self.particles
+            .remove_oldest(count, &mut self.dead_particles);
+        self.emptied |= had_particles && self.particles.is_empty();
+    }
+
+    /// Particles born during the last emission, in the particle system space
+    #[must_use]
+    pub fn born_particles(&self) -> &[Particle] {
+        &self.born_particles
+    }
+
+    /// Particles which died during the last update, in the particle system space
+    #[must_use]
+    pub fn dead_particles(&self) -> &[Particle] {
+        &self.dead_particles
+    }
+
+    /// Particles which collided during the last update, in the particle system space
+    #[must_use]
+    pub fn collided_particles(&self) -> &[Particle] {
+        &self.collided_particles
     }
 
     /// Computes the complete bounding box of the particle system
     #[must_use]
     pub fn compute_aabb(&self) -> Option<Aabb> {
-        if self.particles.is_empty() {
-            return None;
-        }
-        let (x_min, x_max) = match self.iter().map(|p| p.translation.x).minmax() {
-            MinMaxResult::NoElements => return None,
-            MinMaxResult::OneElement(p) => (p, p),
-            MinMaxResult::MinMax(a, b) => (a, b),
-        };
-        let (y_min, y_max) = match self.iter().map(|p| p.translation.y).minmax() {
-            MinMaxResult::NoElements => return None,
-            MinMaxResult::OneElement(p) => (p, p),
-            MinMaxResult::MinMax(a, b) => (a, b),
-        };
-        let (z_min, z_max) = match self.iter().map(|p| p.translation.z).minmax() {
-            MinMaxResult::NoElements => return None,
-            MinMaxResult::OneElement(p) => (p, p),
-            MinMaxResult::MinMax(a, b) => (a, b),
-        };
-        let min = Vec3::new(x_min, y_min, z_min);
-        let max = Vec3::new(x_max, y_max, z_max);
         // TODO: check if this works before enabling
         // if self.world_space {
         //     let matrix = transform.compute_matrix().inverse();
         //     min = matrix.transform_point3(min);
         //     max = matrix.transform_point3(max);
         // }
-        Some(Aabb::from_min_max(min, max))
+        Self::particles_bounds(self.particles.translations())
+            .map(|(min, max)| Aabb::from_min_max(min, max))
+    }
+
+    /// Computes the complete bounding box of the particle system, in parallel chunks for large
+    /// particle systems
+    pub(crate) fn par_compute_aabb(&self, pool: &TaskPool) -> Option<Aabb> {
+        parallel::map_chunks(pool, self.particles.translations(), Self::particles_bounds)
+            .into_iter()
+            .flatten()
+            .reduce(|(a_min, a_max), (b_min, b_max)| (a_min.min(b_min), a_max.max(b_max)))
+            .map(|(min, max)| Aabb::from_min_max(min, max))
+    }
+
+    /// Computes the minimum and maximum of `translations`
+    fn particles_bounds(translations: &[Vec3]) -> Option<(Vec3, Vec3)> {
+        let first = *translations.first()?;
+        Some(
+            translations
+                .iter()
+                .fold((first, first), |(min, max), translation| {
+                    (min.min(*translation), max.max(*translation))
+                }),
+        )
+    }
+
+    /// Toggles [`ParticleSystem::world_space`], converting the current particles to the new space
+    ///
+    /// # Arguments
+    ///
+    /// * `world_space` - The new `world_space` value
+    /// * `transform` - The particle system global transform
+    pub fn set_world_space(&mut self, world_space: bool, transform: &GlobalTransform) {
+        if self.world_space == world_space {
+            return;
+        }
+        let mut matrix = transform.compute_matrix();
+        if !world_space {
+            matrix = matrix.inverse();
+        }
+        for mut particle in self.particles.as_slice_mut().iter_mut() {
+            let transformed = particle.to_particle().transformed(&matrix);
+            particle.set(transformed);
+        }
+        self.particles.transform_trails(&matrix);
+        self.world_space = world_space;
     }
 
     /// Adds a particle to the system
@@ -78,6 +179,7 @@ impl ParticleSystem {
             let matrix = transform.compute_matrix();
             particle = particle.transformed(&matrix);
         }
+        self.born_particles.push(particle.clone());
         self.particles.push(particle);
     }
 
@@ -93,12 +195,13 @@ impl ParticleSystem {
         particles: impl Iterator<Item = Particle>,
         transform: &GlobalTransform,
     ) {
-        if self.world_space {
-            let matrix = transform.compute_matrix();
-            self.particles
-                .extend(particles.map(|p| p.transformed(&matrix)));
-        } else {
-            self.particles.extend(particles);
+        let matrix = self.world_space.then(|| transform.compute_matrix());
+        for mut particle in particles {
+            if let Some(matrix) = &matrix {
+                particle = particle.transformed(matrix);
+            }
+            self.born_particles.push(particle.clone());
+            self.particles.push(particle);
         }
     }
 }
@@ -0,0 +1,243 @@
+use crate::particle::ParticleStorage;
+use crate::{ColorGradient, Curve};
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::{Color, Component, Reflect, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+/// Defines how the particle trails are built
+#[derive(Debug, Copy, Clone, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub enum TrailMode {
+    /// Every particle leaves a trail of its previous positions
+    PerParticle {
+        /// How long a recorded position stays in the trail, in seconds
+        lifetime: f32,
+        /// Minimum distance a particle must travel before a new position is recorded
+        minimum_vertex_distance: f32,
+    },
+    /// A single ribbon connects every particle of the system in emission order, from the newest
+    /// to the oldest particle
+    Ribbon,
+}
+
+/// Renders trails behind the particles of a particle system, as camera facing ribbons using the
+/// particle system material.
+///
+/// Note: The trail of a particle disappears with the particle
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct ParticleTrails {
+    /// How the trails are built
+    pub mode: TrailMode,
+    /// Multiplier of the particle `x` size defining the trail width, evaluated along the trail
+    /// from `0` (the particle) to `1` (the end of the trail)
+    pub width_over_trail: Curve,
+    /// Multiplier of the particle color, evaluated along the trail from `0` (the particle) to `1`
+    /// (the end of the trail)
+    pub color_over_trail: ColorGradient,
+    /// Should the particles still be rendered along with their trails
+    pub render_particles: bool,
+}
+
+/// Single point of a trail ribbon
+#[derive(Debug, Copy, Clone)]
+pub struct TrailStripPoint {
+    /// Position, in the particle system space
+    pub position: Vec3,
+    /// Ribbon width
+    pub width: f32,
+    /// Ribbon color
+    pub color: Color,
+    /// Position along the trail, between `0` and `1`
+    pub ratio: f32,
+}
+
+impl Default for TrailMode {
+    fn default() -> Self {
+        Self::PerParticle {
+            lifetime: 1.0,
+            minimum_vertex_distance: 0.1,
+        }
+    }
+}
+
+impl Default for ParticleTrails {
+    fn default() -> Self {
+        Self {
+            mode: TrailMode::default(),
+            width_over_trail: Curve::linear(1.0, 0.0),
+            color_over_trail: ColorGradient::default(),
+            render_particles: true,
+        }
+    }
+}
+
+impl ParticleTrails {
+    /// Records the particles positions in their trails, in [`TrailMode::PerParticle`] mode
+    pub(crate) fn record(&self, particles: &mut ParticleStorage, delta_time: f32) {
+        if let TrailMode::PerParticle {
+            lifetime,
+            minimum_vertex_distance,
+        } = self.mode
+        {
+            particles.update_trails(delta_time, lifetime, minimum_vertex_distance);
+        }
+    }
+
+    /// Builds the ribbons of `particles`, every ribbon having at least two points
+    pub(crate) fn strips(&self, particles: &ParticleStorage) -> Vec<Vec<TrailStripPoint>> {
+        let point = |index: usize, position: Vec3, ratio: f32| TrailStripPoint {
+            position,
+            width: particles.sizes()[index].x * self.width_over_trail.evaluate(ratio),
+            color: Color::from(
+                Vec4::from(particles.colors()[index].as_rgba_f32())
+                    * Vec4::from(self.color_over_trail.evaluate(ratio).as_rgba_f32()),
+            ),
+            ratio,
+        };
+        match self.mode {
+            TrailMode::PerParticle { lifetime, .. } => {
+                let ratio = |age: f32| {
+                    if lifetime > 0.0 {
+                        (age / lifetime).clamp(0.0, 1.0)
+                    } else {
+                        1.0
+                    }
+                };
+                particles
+                    .trails()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, trail)| {
+                        // The point recorded during the current step is the particle itself
+                        std::iter::once(point(index, particles.translations()[index], 0.0))
+                            .chain(
+                                trail
+                                    .iter()
+                                    .rev()
+                                    .filter(|p| p.age > 0.0)
+                                    .map(|p| point(index, p.position, ratio(p.age))),
+                            )
+                            .collect::<Vec<_>>()
+                    })
+                    .filter(|strip| strip.len() > 1)
+                    .collect()
+            }
+            TrailMode::Ribbon => {
+                if particles.len() < 2 {
+                    return vec![];
+                }
+                let mut indices: Vec<usize> = (0..particles.len()).collect();
+                indices
+                    .sort_unstable_by_key(|i| std::cmp::Reverse(particles.emission_indices()[*i]));
+                let last = (indices.len() - 1) as f32;
+                vec![indices
+                    .into_iter()
+                    .enumerate()
+                    .map(|(k, index)| {
+                        point(index, particles.translations()[index], k as f32 / last)
+                    })
+                    .collect()]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Particle;
+
+    fn per_particle(lifetime: f32, minimum_vertex_distance: f32) -> ParticleTrails {
+        ParticleTrails {
+            mode: TrailMode::PerParticle {
+                lifetime,
+                minimum_vertex_distance,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn storage(translations: &[Vec3]) -> ParticleStorage {
+        let mut storage = ParticleStorage::default();
+        for translation in translations {
+            storage.push(Particle {
+                translation: *translation,
+                ..Default::default()
+            });
+        }
+        storage
+    }
+
+    fn move_to(storage: &mut ParticleStorage, translation: Vec3) {
+        for particle in storage.as_slice_mut().iter_mut() {
+            *particle.translation = translation;
+        }
+    }
+
+    #[test]
+    fn positions_are_recorded_every_minimum_vertex_distance() {
+        let trails = per_particle(1.0, 0.5);
+        let mut storage = storage(&[Vec3::ZERO]);
+        trails.record(&mut storage, 0.1);
+        assert_eq!(storage.trails()[0].len(), 1);
+        move_to(&mut storage, Vec3::X * 0.3);
+        trails.record(&mut storage, 0.1);
+        assert_eq!(storage.trails()[0].len(), 1);
+        move_to(&mut storage, Vec3::X * 0.6);
+        trails.record(&mut storage, 0.1);
+        let positions: Vec<_> = storage.trails()[0].iter().map(|p| p.position).collect();
+        assert_eq!(positions, [Vec3::ZERO, Vec3::X * 0.6]);
+        // The strip starts at the particle, the point recorded during the current step is skipped
+        let strips = trails.strips(&storage);
+        assert_eq!(strips.len(), 1);
+        let positions: Vec<_> = strips[0].iter().map(|p| p.position).collect();
+        assert_eq!(positions, [Vec3::X * 0.6, Vec3::ZERO]);
+        assert!((strips[0][1].ratio - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn old_positions_are_removed() {
+        let trails = per_particle(0.25, 0.0);
+        let mut storage = storage(&[Vec3::ZERO]);
+        for step in 0..5 {
+            move_to(&mut storage, Vec3::X * step as f32);
+            trails.record(&mut storage, 0.1);
+        }
+        let ages: Vec<_> = storage.trails()[0].iter().map(|p| p.age).collect();
+        assert_eq!(ages.len(), 3);
+        assert!(ages.iter().all(|age| *age <= 0.25));
+    }
+
+    #[test]
+    fn zero_lifetime_trails_are_empty() {
+        let trails = per_particle(0.0, 0.0);
+        let mut storage = storage(&[Vec3::ZERO]);
+        for step in 0..3 {
+            move_to(&mut storage, Vec3::X * step as f32);
+            trails.record(&mut storage, 0.1);
+        }
+        assert!(trails.strips(&storage).is_empty());
+    }
+
+    #[test]
+    fn ribbon_connects_particles_in_emission_order() {
+        let trails = ParticleTrails {
+            mode: TrailMode::Ribbon,
+            ..Default::default()
+        };
+        let mut storage = storage(&[Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z]);
+        // Moves the newest particle in place of the oldest one
+        storage.remove_oldest(1, &mut Vec::new());
+        let strips = trails.strips(&storage);
+        assert_eq!(strips.len(), 1);
+        let positions: Vec<_> = strips[0].iter().map(|p| p.position).collect();
+        assert_eq!(positions, [Vec3::Z, Vec3::Y, Vec3::X]);
+        let ratios: Vec<_> = strips[0].iter().map(|p| p.ratio).collect();
+        assert_eq!(ratios, [0.0, 0.5, 1.0]);
+        // A single particle has no ribbon
+        storage.remove_oldest(2, &mut Vec::new());
+        assert!(trails.strips(&storage).is_empty());
+    }
+}
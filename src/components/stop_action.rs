@@ -0,0 +1,40 @@
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::ecs::system::Commands;
+use bevy::prelude::{Component, DespawnRecursiveExt, Entity, Reflect, Visibility};
+use serde::{Deserialize, Serialize};
+
+/// Action applied to a particle system once it is finished, meaning its non looping
+/// [`EmitterDuration::FixedDuration`](crate::EmitterDuration) elapsed and it holds no particles
+#[derive(Debug, Copy, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+pub enum StopAction {
+    /// Nothing happens
+    #[default]
+    None,
+    /// The particle system entity is despawned
+    Despawn,
+    /// The particle system entity and its children are despawned
+    DespawnRecursive,
+    /// The particle system entity is hidden, its components are kept
+    Disable,
+    /// Calls the given function with the particle system entity, can't be serialized
+    #[serde(skip)]
+    Callback(fn(Entity, &mut Commands)),
+}
+
+impl StopAction {
+    /// Applies the action to the particle system `entity`
+    pub(crate) fn apply(self, entity: Entity, commands: &mut Commands) {
+        match self {
+            Self::None => (),
+            Self::Despawn => commands.entity(entity).despawn(),
+            Self::DespawnRecursive => commands.entity(entity).despawn_recursive(),
+            Self::Disable => {
+                commands
+                    .entity(entity)
+                    .insert(Visibility { is_visible: false });
+            }
+            Self::Callback(callback) => callback(entity, commands),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{Particle, ParticleEmitter, ParticleParams, ParticleSystem, RangeOrFixed};
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::{Component, Entity, GlobalTransform, Mat4, Reflect, Vec4};
+use bevy::reflect::FromReflect;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Defines when a [`SubEmitter`] emits particles
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub enum SubEmitterTrigger {
+    /// Particles are emitted when a particle is born
+    Birth,
+    /// Particles are emitted when a particle dies
+    Death,
+    /// Particles are emitted when a particle collides, requires a
+    /// [`ParticleCollision`](crate::modifiers::ParticleCollision) modifier
+    Collision,
+}
+
+/// Emits particles in another particle system when a particle of the current system triggers it.
+///
+/// The `target` entity must be a particle system (see
+/// [`ParticleSystemBundle`](crate::prelude::ParticleSystemBundle)): its [`ParticleEmitter`] shape
+/// and [`ParticleParams`] are used to create the emitted particles, which are then placed at the
+/// position of the triggering particle.
+///
+/// Note: You probably want to set the `target` emission `rate` to `0.0` and leave its `bursts`
+/// empty so it only emits particles through the sub emitter
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct SubEmitter {
+    /// The particle system entity receiving the emitted particles
+    pub target: Entity,
+    /// The emission trigger
+    pub trigger: SubEmitterTrigger,
+    /// The amount of particles emitted each time the sub emitter is triggered
+    pub count: RangeOrFixed<usize>,
+    /// Ratio of the triggering particle velocity added to the emitted particles
+    pub inherit_velocity: f32,
+    /// The emitted particles color is multiplied by the triggering particle color
+    pub inherit_color: bool,
+    /// The emitted particles size and start size are multiplied by the triggering particle size
+    pub inherit_size: bool,
+}
+
+/// Sub emitters of a [`ParticleSystem`]
+#[derive(Debug, Clone, Default, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct SubEmitters(pub Vec<SubEmitter>);
+
+impl FromReflect for SubEmitter {
+    fn from_reflect(reflect: &dyn Reflect) -> Option<Self> {
+        reflect.any().downcast_ref::<Self>().cloned()
+    }
+}
+
+impl SubEmitter {
+    /// Creates a new sub emitter with no inheritance
+    #[must_use]
+    pub const fn new(
+        target: Entity,
+        trigger: SubEmitterTrigger,
+        count: RangeOrFixed<usize>,
+    ) -> Self {
+        Self {
+            target,
+            trigger,
+            count,
+            inherit_velocity: 0.0,
+            inherit_color: false,
+            inherit_size: false,
+        }
+    }
+
+    /// Retrieves the particles triggering the sub emitter in `particle_system`, in world space
+    pub(crate) fn triggering_particles(
+        &self,
+        particle_system: &ParticleSystem,
+        transform: &GlobalTransform,
+    ) -> Vec<Particle> {
+        let particles = match self.trigger {
+            SubEmitterTrigger::Birth => particle_system.born_particles(),
+            SubEmitterTrigger::Death => particle_system.dead_particles(),
+            SubEmitterTrigger::Collision => particle_system.collided_particles(),
+        };
+        if particle_system.world_space {
+            particles.to_vec()
+        } else {
+            let matrix = transform.compute_matrix();
+            particles
+                .iter()
+                .cloned()
+                .map(|p| p.transformed(&matrix))
+                .collect()
+        }
+    }
+
+    /// Emits particles in the `target` particle system for each of the `triggering_particles`
+    ///
+    /// # Arguments
+    ///
+    /// * `triggering_particles` - The particles triggering the emission, in world space
+    /// * `target` - The target particle system components
+    /// * `rng` - random generator
+    pub(crate) fn emit(
+        &self,
+        triggering_particles: &[Particle],
+        (particle_system, emitter, params, transform): (
+            &mut ParticleSystem,
+            &mut ParticleEmitter,
+            &ParticleParams,
+            &GlobalTransform,
+        ),
+        rng: &mut impl Rng,
+    ) {
+        let matrix = if particle_system.world_space {
+            Mat4::IDENTITY
+        } else {
+            transform.compute_matrix().inverse()
+        };
+        for parent in triggering_particles {
+            let count = self.count.evaluate_rng(rng);
+            let emitter_time = emitter.cycle_ratio();
+            let new_particles = emitter.emit_count(count, rng).into_iter().map(|e| {
+                let mut particle = params.get_particle(
+                    parent.translation + e.position,
+                    e.direction,
+                    emitter_time,
+                    rng,
+                );
+                particle.velocity += parent.velocity * self.inherit_velocity;
+                if self.inherit_color {
+                    particle.color *= Vec4::from(parent.color);
+                }
+                if self.inherit_size {
+                    particle.size *= parent.size;
+                    particle.start_size *= parent.size;
+                }
+                particle.transformed(&matrix)
+            });
+            // The particles are already in the target particle system space
+            particle_system.extend(new_particles, &GlobalTransform::identity());
+        }
+    }
+}
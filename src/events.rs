@@ -0,0 +1,56 @@
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::{Color, Component, Entity, Reflect, Vec3};
+
+/// Event sent when a particle system emitter starts emitting.
+///
+/// Looping emitters send this event at the start of every loop
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParticleSystemStarted {
+    /// The particle system entity
+    pub entity: Entity,
+}
+
+/// Event sent when a particle system emitter fires a [`Burst`](crate::Burst)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BurstFired {
+    /// The particle system entity
+    pub entity: Entity,
+    /// The index of the burst in [`ParticleEmitter::bursts`](crate::ParticleEmitter::bursts)
+    pub index: usize,
+}
+
+/// Event sent when a non looping [`EmitterDuration::FixedDuration`](crate::EmitterDuration)
+/// elapses, the particle system won't emit any more particles
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EmitterFinished {
+    /// The particle system entity
+    pub entity: Entity,
+}
+
+/// Event sent when a particle system holding particles becomes empty.
+///
+/// Its last particle either died or was removed by a
+/// [`MaxParticleCount`](crate::modifiers::MaxParticleCount) modifier, or the particle system was
+/// cleared
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParticleSystemEmpty {
+    /// The particle system entity
+    pub entity: Entity,
+}
+
+/// Event sent for every particle death of particle systems with a [`ParticleDeathEvents`]
+/// component
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParticleDied {
+    /// The particle system entity
+    pub entity: Entity,
+    /// The world space position of the particle
+    pub position: Vec3,
+    /// The particle color
+    pub color: Color,
+}
+
+/// Enables [`ParticleDied`] events for a particle system
+#[derive(Debug, Copy, Clone, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct ParticleDeathEvents;
@@ -29,52 +29,90 @@
 
 extern crate core;
 
+mod asset;
 mod bundle;
 /// Particle system components
 pub mod components;
+mod events;
 mod particle;
 mod render;
+mod simulation;
 mod systems;
 /// Utility structs
 pub mod utilities;
 
-use crate::render::draw::DrawParticle;
-use crate::render::pipeline::{ParticlePipeline, PARTICLE_SHADER_HANDLE};
-use crate::render::{ExtractedParticles, ParticleImageBindGroups, ParticleMeta};
-use bevy::core_pipeline::Transparent3d;
+use crate::render::draw::{DrawParticle, DrawParticleMesh};
+use crate::render::pipeline::{
+    ParticleMeshPipeline, ParticlePipeline, PARTICLE_MESH_SHADER_HANDLE, PARTICLE_SHADER_HANDLE,
+    PARTICLE_TYPES_SHADER_HANDLE,
+};
+use crate::render::{
+    ExtractedCustomMaterials, ExtractedParticles, ParticleImageBindGroups, ParticleMeta,
+};
+use bevy::core_pipeline::{AlphaMask3d, Transparent3d};
 use bevy::log;
 use bevy::prelude::*;
 use bevy::render::{
-    render_phase::AddRenderCommand, render_resource::SpecializedRenderPipelines, RenderApp,
-    RenderStage,
+    render_phase::AddRenderCommand,
+    render_resource::{SpecializedMeshPipelines, SpecializedRenderPipelines},
+    RenderApp, RenderStage,
 };
 #[cfg(feature = "inspector")]
 use bevy_inspector_egui::RegisterInspectable;
 
 ///
 pub mod prelude {
-    pub use crate::bundle::ParticleSystemBundle;
+    pub use crate::asset::{
+        ParticleEffect, ParticleEffectLoader, ParticleEffectMaterial, ParticleEffectModifier,
+        ParticleEffectModifiers, ParticleEffectRenderMode,
+    };
+    pub use crate::bundle::{ParticleEffectBundle, ParticleSystemBundle};
     pub use crate::components::*;
-    pub use crate::particle::Particle;
+    pub use crate::events::{
+        BurstFired, EmitterFinished, ParticleDeathEvents, ParticleDied, ParticleSystemEmpty,
+        ParticleSystemStarted,
+    };
+    pub use crate::particle::{Particle, ParticleMut, ParticleStorage, TrailPoint};
+    pub use crate::render::custom_material::{
+        CustomParticleMaterial, CustomParticleMaterialPlugin, CustomParticlePipeline,
+    };
+    pub use crate::simulation::{
+        AddParticleModifier, InspectableModifier, ParticleSimulation, ParticleTimeStep,
+    };
     pub use crate::utilities::*;
     pub use crate::ParticlesPlugin;
 }
 
+#[cfg(feature = "inspector")]
+use crate::modifiers::PerlinNoise;
 use crate::modifiers::{
-    AngularVelocityOverTime, ColorOverLifeTime, ColorOverSpeed, LinearVelocityOverLifeTime,
-    MaxParticleCount, MaxParticleSize, MaxParticleSpeed, OrbitalVelocityOverLifeTime,
-    ParticleGravity, PerlinNoise, RotationOverTime, RotationOverVelocity, SizeOverSpeed,
-    SizeOverTime, SpeedOverTime, VelocityOverTime,
+    AlphaOverLifetime, AngularVelocityOverTime, AngularVelocityOverTime3d, Collider,
+    ColorOverLifeTime, ColorOverSpeed, LinearVelocityOverLifeTime, MaxParticleCount,
+    MaxParticleSize, MaxParticleSpeed, MeshCollider, OrbitalVelocityOverLifeTime,
+    ParticleCollision, ParticleGravity, ParticleModifiers, RotationOverLifetime, RotationOverTime,
+    RotationOverTime3d, RotationOverVelocity, SizeOverLifetime, SizeOverSpeed, SizeOverTime,
+    SpeedOverLifetime, SpeedOverTime, VelocityOverTime,
 };
 use prelude::*;
-use systems::{apply_modifier, apply_rng_modifier, apply_system_modifier};
-
-const PARTICLE_UPDATE: &str = "particle_update";
-const PARTICLE_EMISSION: &str = "particle_emission";
 
 /// Particle System plugin
 pub struct ParticlesPlugin;
 
+/// Particle simulation system labels, used to order systems in the [`ParticleSimulation`] stage
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum ParticleSystemLabel {
+    /// Particle update system, ageing and moving the particles
+    Update,
+    /// Particle emission system
+    Emission,
+    /// Every particle modifier system
+    Modifier,
+    /// Particle collision system
+    Collision,
+    /// Sub emitters system
+    SubEmitters,
+}
+
 /// Particle render system labels
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum ParticleLabel {
@@ -87,8 +125,12 @@ pub enum ParticleLabel {
 }
 
 impl Plugin for ParticlesPlugin {
+    #[allow(clippy::too_many_lines)]
     fn build(&self, app: &mut App) {
         app.register_type::<ParticleGravity>()
+            .register_type::<ParticleCollision>()
+            .register_type::<Collider>()
+            .register_type::<MeshCollider>()
             .register_type::<MaxParticleSpeed>()
             .register_type::<MaxParticleCount>()
             .register_type::<MaxParticleSize>()
@@ -97,66 +139,111 @@ impl Plugin for ParticlesPlugin {
             .register_type::<ParticleEmitter>()
             .register_type::<ParticleSystem>()
             .register_type::<ParticleRenderMode>()
-            .register_type::<ColorGradient>();
+            .register_type::<StopAction>()
+            .register_type::<ParticleTrails>()
+            .register_type::<SubEmitters>()
+            .register_type::<ParticlePlayback>()
+            .register_type::<ParticleDeathEvents>()
+            .register_type::<ColorGradient>()
+            .register_type::<Curve>()
+            .register_type::<SizeOverLifetime>()
+            .register_type::<SpeedOverLifetime>()
+            .register_type::<RotationOverLifetime>()
+            .register_type::<AlphaOverLifetime>()
+            .register_type::<AngularVelocityOverTime>()
+            .register_type::<AngularVelocityOverTime3d>()
+            .register_type::<ColorOverLifeTime>()
+            .register_type::<ColorOverSpeed>()
+            .register_type::<LinearVelocityOverLifeTime>()
+            .register_type::<OrbitalVelocityOverLifeTime>()
+            .register_type::<RotationOverTime>()
+            .register_type::<RotationOverTime3d>()
+            .register_type::<RotationOverVelocity>()
+            .register_type::<SizeOverSpeed>()
+            .register_type::<SizeOverTime>()
+            .register_type::<SpeedOverTime>()
+            .register_type::<VelocityOverTime>()
+            .register_type::<ParticleModifiers>()
+            .add_asset::<ParticleEffect>()
+            .init_asset_loader::<ParticleEffectLoader>()
+            .add_event::<ParticleSystemStarted>()
+            .add_event::<BurstFired>()
+            .add_event::<EmitterFinished>()
+            .add_event::<ParticleSystemEmpty>()
+            .add_event::<ParticleDied>();
         #[cfg(feature = "inspector")]
         app.init_resource::<bevy_inspector_egui::InspectableRegistry>()
             .register_inspectable::<RotationMode>()
+            .register_inspectable::<StartSize>()
+            .register_inspectable::<StartRotation3d>()
             .register_inspectable::<ParticleMaterial>()
             .register_inspectable::<ParticleSystem>()
             .register_inspectable::<ParticleRenderMode>()
+            .register_inspectable::<ParticleTrails>()
             .register_inspectable::<ParticleEmitter>()
+            .register_inspectable::<ParticlePlayback>()
             .register_inspectable::<ColorOverLifeTime>()
             .register_inspectable::<ColorOverSpeed>()
+            .register_inspectable::<AlphaOverLifetime>()
+            .register_inspectable::<Curve>()
             .register_inspectable::<RangeOrFixed<f32>>()
             .register_inspectable::<RangeOrFixed<usize>>()
             .register_inspectable::<AngularVelocityOverTime>()
+            .register_inspectable::<AngularVelocityOverTime3d>()
             .register_inspectable::<SpeedOverTime>()
+            .register_inspectable::<SpeedOverLifetime>()
             .register_inspectable::<VelocityOverTime>()
             .register_inspectable::<OrbitalVelocityOverLifeTime>()
             .register_inspectable::<LinearVelocityOverLifeTime>()
             .register_inspectable::<ParticleGravity>()
             .register_inspectable::<SizeOverTime>()
             .register_inspectable::<SizeOverSpeed>()
+            .register_inspectable::<SizeOverLifetime>()
             .register_inspectable::<RotationOverVelocity>()
             .register_inspectable::<RotationOverTime>()
+            .register_inspectable::<RotationOverTime3d>()
+            .register_inspectable::<RotationOverLifetime>()
             .register_inspectable::<PerlinNoise>()
-            .register_inspectable::<ColorOrGradient>();
-
-        app.add_system(systems::update_particle_system.label(PARTICLE_UPDATE))
-            .add_system(systems::emit_particles.label(PARTICLE_EMISSION))
-            .add_system(systems::compute_particles_aabb.after(PARTICLE_UPDATE))
-            // TODO: merge all systems in one to avoid so many queries
-            .add_system(apply_system_modifier::<MaxParticleCount>.after(PARTICLE_EMISSION))
-            .add_system(apply_modifier::<MaxParticleSize>.after(PARTICLE_EMISSION))
-            .add_system(apply_modifier::<ParticleGravity>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<MaxParticleSpeed>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<SpeedOverTime>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<VelocityOverTime>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<LinearVelocityOverLifeTime>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<OrbitalVelocityOverLifeTime>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<AngularVelocityOverTime>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<SizeOverTime>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<SizeOverSpeed>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<RotationOverVelocity>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<RotationOverTime>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<ColorOverLifeTime>.after(PARTICLE_UPDATE))
-            .add_system(apply_modifier::<ColorOverSpeed>.after(PARTICLE_UPDATE))
-            .add_system(apply_rng_modifier::<PerlinNoise>.after(PARTICLE_UPDATE));
+            .register_inspectable::<Collider>()
+            .register_inspectable::<ParticleCollision>()
+            .register_inspectable::<ColorOrGradient>()
+            .register_inspectable::<ParticleModifiers>();
+
+        let mut simulation = ParticleSimulation::default();
+        simulation
+            .add_system_modifier::<MaxParticleCount>()
+            .add_modifier_stack();
+        app.insert_resource(simulation)
+            .init_resource::<ParticleTimeStep>()
+            .add_system(systems::spawn_particle_effects)
+            .add_system(systems::reload_particle_effects)
+            .add_system(systems::update_mesh_colliders)
+            .add_system(systems::run_particle_simulation.exclusive_system().at_end());
 
         let mut shaders = app
             .world
             .get_resource_mut::<Assets<Shader>>()
             .expect("Could not load the `Assets<Shader>` resource from the world");
+        let particle_types_shader = Shader::from_wgsl(include_str!("render/particle_types.wgsl"));
+        shaders.set_untracked(PARTICLE_TYPES_SHADER_HANDLE, particle_types_shader);
         let particle_shader = Shader::from_wgsl(include_str!("render/particles.wgsl"));
         shaders.set_untracked(PARTICLE_SHADER_HANDLE, particle_shader);
+        let particle_mesh_shader = Shader::from_wgsl(include_str!("render/particle_mesh.wgsl"));
+        shaders.set_untracked(PARTICLE_MESH_SHADER_HANDLE, particle_mesh_shader);
         if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<ParticleImageBindGroups>()
                 .init_resource::<ParticlePipeline>()
                 .init_resource::<SpecializedRenderPipelines<ParticlePipeline>>()
+                .init_resource::<ParticleMeshPipeline>()
+                .init_resource::<SpecializedMeshPipelines<ParticleMeshPipeline>>()
                 .init_resource::<ParticleMeta>()
                 .init_resource::<ExtractedParticles>()
+                .init_resource::<ExtractedCustomMaterials>()
                 .add_render_command::<Transparent3d, DrawParticle>()
+                .add_render_command::<Transparent3d, DrawParticleMesh>()
+                .add_render_command::<AlphaMask3d, DrawParticle>()
+                .add_render_command::<AlphaMask3d, DrawParticleMesh>()
                 .add_system_to_stage(
                     RenderStage::Extract,
                     render::extract::extract_particles.label(ParticleLabel::ExtractParticles),
@@ -173,3 +260,326 @@ impl Plugin for ParticlesPlugin {
         log::info!("Loaded Particles Plugin");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::asset::AssetPlugin;
+    use bevy::ecs::event::{Events, ManualEventReader};
+    use bevy::render::texture::Image;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Shader>()
+            .add_asset::<Mesh>()
+            .add_asset::<Image>()
+            .add_plugin(ParticlesPlugin);
+        app
+    }
+
+    /// Spawns a particle system emitting particles living for `0.2` seconds with `emitter`, and
+    /// simulated `seconds` ahead
+    fn spawn(app: &mut App, emitter: ParticleEmitter, seconds: f32) -> Entity {
+        let mut playback = ParticlePlayback::default();
+        playback.simulate(seconds);
+        app.world
+            .spawn()
+            .insert_bundle(ParticleSystemBundle {
+                particle_emitter: emitter,
+                particle_params: ParticleParams {
+                    start_lifetime: 0.2.into(),
+                    ..Default::default()
+                },
+                playback,
+                ..Default::default()
+            })
+            .id()
+    }
+
+    /// Updates `app` after a few milliseconds, so the frame has a non zero delta time
+    fn update_later(app: &mut App) {
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        app.update();
+    }
+
+    /// Copies the lifetimes of the particles of `entity`
+    fn lifetimes(app: &App, entity: Entity) -> Vec<f32> {
+        app.world
+            .get::<ParticleSystem>(entity)
+            .unwrap()
+            .lifetimes()
+            .to_vec()
+    }
+
+    /// Entities of the events sent since the last call with `reader`
+    fn read<E: Copy + Send + Sync + 'static>(
+        app: &App,
+        reader: &mut ManualEventReader<E>,
+    ) -> Vec<E> {
+        let events = app.world.resource::<Events<E>>();
+        reader.iter(events).copied().collect()
+    }
+
+    #[test]
+    fn finished_emitter_sends_finished_and_empty_events() {
+        let mut app = app();
+        let entity = spawn(
+            &mut app,
+            ParticleEmitter {
+                duration: EmitterDuration::FixedDuration {
+                    duration: 0.5,
+                    looping: false,
+                },
+                rate: 20.0.into(),
+                ..Default::default()
+            },
+            1.0,
+        );
+        let mut finished = ManualEventReader::<EmitterFinished>::default();
+        let mut empty = ManualEventReader::<ParticleSystemEmpty>::default();
+        app.update();
+        assert_eq!(read(&app, &mut finished), [EmitterFinished { entity }]);
+        assert_eq!(read(&app, &mut empty), [ParticleSystemEmpty { entity }]);
+        assert!(app.world.get::<ParticleSystem>(entity).unwrap().is_empty());
+        app.update();
+        assert!(read(&app, &mut finished).is_empty());
+        assert!(read(&app, &mut empty).is_empty());
+    }
+
+    #[test]
+    fn stop_actions_are_applied_to_finished_particle_systems() {
+        let mut app = app();
+        let emitter = ParticleEmitter {
+            duration: EmitterDuration::FixedDuration {
+                duration: 0.5,
+                looping: false,
+            },
+            rate: 20.0.into(),
+            ..Default::default()
+        };
+        let despawned = spawn(&mut app, emitter.clone(), 1.0);
+        let disabled = spawn(&mut app, emitter.clone(), 1.0);
+        let running = spawn(&mut app, emitter, 0.6);
+        app.world.entity_mut(despawned).insert(StopAction::Despawn);
+        app.world.entity_mut(disabled).insert(StopAction::Disable);
+        app.world.entity_mut(running).insert(StopAction::Disable);
+        app.update();
+        assert!(app.world.get_entity(despawned).is_none());
+        assert!(!app.world.get::<Visibility>(disabled).unwrap().is_visible);
+        // The emitter is finished but its last particles are still alive
+        assert!(!app.world.get::<ParticleSystem>(running).unwrap().is_empty());
+        assert!(app.world.get::<Visibility>(running).unwrap().is_visible);
+    }
+
+    #[test]
+    fn reloaded_effects_only_remove_their_own_modifiers() {
+        let mut app = app();
+        let effect = ParticleEffect {
+            world_space: false,
+            emitter: ParticleEmitter::default(),
+            params: ParticleParams::default(),
+            material: ParticleEffectMaterial::Image {
+                path: "smoke.png".to_string(),
+                blend_mode: ParticleBlendMode::default(),
+                alpha_cutoff: 0.5,
+                handle: Handle::default(),
+            },
+            render_mode: ParticleEffectRenderMode::default(),
+            modifiers: vec![
+                ParticleEffectModifier::ParticleGravity(ParticleGravity::default()),
+                ParticleEffectModifier::SizeOverTime(SizeOverTime(Vec3::ONE)),
+            ],
+            trails: None,
+            stop_action: StopAction::None,
+            seed: None,
+        };
+        let handle = app
+            .world
+            .resource_mut::<Assets<ParticleEffect>>()
+            .add(effect);
+        let entity = app
+            .world
+            .spawn()
+            .insert_bundle(ParticleEffectBundle {
+                effect: handle.clone(),
+                ..Default::default()
+            })
+            .id();
+        app.update();
+        assert!(app.world.get::<ParticleGravity>(entity).is_some());
+        app.world.entity_mut(entity).insert(MaxParticleSpeed(10.0));
+        app.world
+            .resource_mut::<Assets<ParticleEffect>>()
+            .get_mut(&handle)
+            .unwrap()
+            .modifiers = vec![ParticleEffectModifier::SizeOverTime(SizeOverTime(Vec3::X))];
+        // The modification event is sent at the end of the frame
+        app.update();
+        app.update();
+        assert!(app.world.get::<ParticleGravity>(entity).is_none());
+        assert_eq!(app.world.get::<SizeOverTime>(entity).unwrap().0, Vec3::X);
+        assert!(app.world.get::<MaxParticleSpeed>(entity).is_some());
+    }
+
+    #[test]
+    fn stop_and_clear_sends_an_empty_event() {
+        let mut app = app();
+        let entity = spawn(&mut app, ParticleEmitter::default(), 0.5);
+        let mut empty = ManualEventReader::<ParticleSystemEmpty>::default();
+        app.update();
+        assert!(!app.world.get::<ParticleSystem>(entity).unwrap().is_empty());
+        assert!(read(&app, &mut empty).is_empty());
+        app.world
+            .get_mut::<ParticlePlayback>(entity)
+            .unwrap()
+            .stop(true);
+        app.update();
+        assert!(app.world.get::<ParticleSystem>(entity).unwrap().is_empty());
+        assert_eq!(read(&app, &mut empty), [ParticleSystemEmpty { entity }]);
+        // An empty particle system being cleared is not emptied again
+        app.world.get_mut::<ParticleSystem>(entity).unwrap().clear();
+        app.update();
+        assert!(read(&app, &mut empty).is_empty());
+    }
+
+    #[test]
+    fn max_particle_count_sends_an_empty_event() {
+        let mut app = app();
+        let emitter = ParticleEmitter {
+            rate: 100.0.into(),
+            ..Default::default()
+        };
+        let entity = spawn(&mut app, emitter, 0.1);
+        app.world.entity_mut(entity).insert(MaxParticleCount(0));
+        let mut empty = ManualEventReader::<ParticleSystemEmpty>::default();
+        app.update();
+        assert!(app.world.get::<ParticleSystem>(entity).unwrap().is_empty());
+        let events = read(&app, &mut empty);
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|e| e.entity == entity));
+    }
+
+    #[test]
+    fn paused_particle_systems_do_not_age() {
+        let mut app = app();
+        let emitter = ParticleEmitter {
+            rate: 100.0.into(),
+            ..Default::default()
+        };
+        let entity = spawn(&mut app, emitter, 0.1);
+        app.update();
+        app.world
+            .get_mut::<ParticlePlayback>(entity)
+            .unwrap()
+            .pause();
+        update_later(&mut app);
+        let paused_lifetimes = lifetimes(&app, entity);
+        assert!(!paused_lifetimes.is_empty());
+        update_later(&mut app);
+        update_later(&mut app);
+        assert_eq!(lifetimes(&app, entity), paused_lifetimes);
+        app.world
+            .get_mut::<ParticlePlayback>(entity)
+            .unwrap()
+            .play();
+        update_later(&mut app);
+        let lifetimes = lifetimes(&app, entity);
+        assert!(lifetimes
+            .iter()
+            .zip(&paused_lifetimes)
+            .all(|(lifetime, paused)| lifetime < paused));
+    }
+
+    #[test]
+    fn stopped_particle_systems_keep_or_clear_their_particles() {
+        let mut app = app();
+        let emitter = ParticleEmitter {
+            rate: 100.0.into(),
+            ..Default::default()
+        };
+        let kept = spawn(&mut app, emitter.clone(), 0.1);
+        let cleared = spawn(&mut app, emitter, 0.1);
+        app.update();
+        app.world
+            .get_mut::<ParticlePlayback>(kept)
+            .unwrap()
+            .stop(false);
+        app.world
+            .get_mut::<ParticlePlayback>(cleared)
+            .unwrap()
+            .stop(true);
+        update_later(&mut app);
+        assert!(!lifetimes(&app, kept).is_empty());
+        assert!(lifetimes(&app, cleared).is_empty());
+        // Stopped particle systems don't emit anymore
+        app.world
+            .get_mut::<ParticlePlayback>(kept)
+            .unwrap()
+            .simulate(1.0);
+        app.world
+            .get_mut::<ParticlePlayback>(cleared)
+            .unwrap()
+            .simulate(1.0);
+        update_later(&mut app);
+        assert!(lifetimes(&app, kept).is_empty());
+        assert!(lifetimes(&app, cleared).is_empty());
+    }
+
+    #[test]
+    fn non_finite_simulation_durations_are_ignored() {
+        let mut app = app();
+        let emitter = ParticleEmitter {
+            rate: 100.0.into(),
+            ..Default::default()
+        };
+        let entity = spawn(&mut app, emitter, f32::INFINITY);
+        let mut playback = app.world.get_mut::<ParticlePlayback>(entity).unwrap();
+        playback.simulate(f32::NAN);
+        playback.simulate(0.1);
+        playback.simulate(f32::INFINITY);
+        app.update();
+        // Only the finite duration is simulated
+        let lifetimes = lifetimes(&app, entity);
+        assert!((8..=12).contains(&lifetimes.len()));
+        assert!(lifetimes.iter().all(|lifetime| lifetime.is_finite()));
+        let playback = app.world.get::<ParticlePlayback>(entity).unwrap();
+        assert!(playback.pending_simulation.abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn prewarm_fills_looping_emitters_on_their_first_frame() {
+        let mut app = app();
+        let emitter = ParticleEmitter {
+            duration: EmitterDuration::FixedDuration {
+                duration: 1.0,
+                looping: true,
+            },
+            rate: 50.0.into(),
+            ..Default::default()
+        };
+        let spawn_emitter = |app: &mut App, prewarm: bool| {
+            app.world
+                .spawn()
+                .insert_bundle(ParticleSystemBundle {
+                    particle_emitter: ParticleEmitter {
+                        prewarm,
+                        ..emitter.clone()
+                    },
+                    particle_params: ParticleParams {
+                        start_lifetime: 2.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .id()
+        };
+        let prewarmed = spawn_emitter(&mut app, true);
+        let cold = spawn_emitter(&mut app, false);
+        app.update();
+        // A full emission cycle is simulated
+        assert!((48..=52).contains(&lifetimes(&app, prewarmed).len()));
+        assert!(lifetimes(&app, cold).len() <= 1);
+    }
+}
@@ -0,0 +1,476 @@
+mod storage;
+
+use bevy::math::EulerRot;
+use bevy::prelude::{Color, Mat4, Quat, Reflect, Vec3, Vec4};
+use bevy::reflect::FromReflect;
+pub use storage::{ParticleSliceMut, ParticleStorage};
+
+/// Position recorded in a particle trail
+#[derive(Debug, Copy, Clone)]
+pub struct TrailPoint {
+    /// Recorded position, in the particle system space
+    pub position: Vec3,
+    /// Time elapsed since the position was recorded
+    pub age: f32,
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub enum ParticleRotation {
+    AlignToDirection {
+        offset: f32,
+    },
+    FreeRotation {
+        rotation: f32,
+        angular_velocity: f32,
+    },
+    FreeRotation3d {
+        rotation: Quat,
+        angular_velocity: Vec3,
+    },
+}
+
+/// Single particle representation
+#[derive(Debug, Clone, Reflect)]
+pub struct Particle {
+    /// 3D position
+    pub translation: Vec3,
+    /// Particle rotation, either a 1D `z` rotation for camera facing billboards or a full 3D rotation
+    pub(crate) rotation: ParticleRotation,
+    /// Size of the particle on each axis. Billboards only use `x` and `y`
+    pub size: Vec3,
+    /// Start size of the particle on each axis
+    pub start_size: Vec3,
+    /// Lifetime of the particle
+    pub lifetime: f32,
+    /// Start Lifetime of the particle
+    pub start_lifetime: f32,
+    /// Particle color
+    pub color: Color,
+    /// Particle 3D velocity
+    pub velocity: Vec3,
+    pub(crate) start_direction: Vec3,
+    /// First custom data slot, free for user modifiers and forwarded to the shader
+    pub custom_1: Vec4,
+    /// Second custom data slot, free for user modifiers and forwarded to the shader
+    pub custom_2: Vec4,
+}
+
+/// Mutable access to a single particle, whose attributes are stored in separate arrays (see
+/// [`ParticleStorage`]).
+///
+/// Particle modifiers only read and write the attributes they need, through this view
+#[derive(Debug)]
+pub struct ParticleMut<'a> {
+    /// 3D position
+    pub translation: &'a mut Vec3,
+    /// Particle rotation, either a 1D `z` rotation for camera facing billboards or a full 3D rotation
+    pub(crate) rotation: &'a mut ParticleRotation,
+    /// Size of the particle on each axis. Billboards only use `x` and `y`
+    pub size: &'a mut Vec3,
+    /// Start size of the particle on each axis
+    pub start_size: &'a mut Vec3,
+    /// Lifetime of the particle
+    pub lifetime: &'a mut f32,
+    /// Start Lifetime of the particle
+    pub start_lifetime: &'a mut f32,
+    /// Particle color
+    pub color: &'a mut Color,
+    /// Particle 3D velocity
+    pub velocity: &'a mut Vec3,
+    pub(crate) start_direction: &'a mut Vec3,
+    /// First custom data slot, free for user modifiers and forwarded to the shader
+    pub custom_1: &'a mut Vec4,
+    /// Second custom data slot, free for user modifiers and forwarded to the shader
+    pub custom_2: &'a mut Vec4,
+}
+
+impl ParticleRotation {
+    /// Retrieves the `z` rotation value, `direction` being the current particle direction
+    #[inline]
+    fn value(&self, direction: impl FnOnce() -> Vec3) -> f32 {
+        match self {
+            Self::AlignToDirection { offset } => {
+                let direction = direction();
+                direction.y.atan2(direction.x) + offset
+            }
+            Self::FreeRotation { rotation, .. } => *rotation,
+            Self::FreeRotation3d { rotation, .. } => rotation.to_euler(EulerRot::ZYX).0,
+        }
+    }
+
+    /// Retrieves the 3D rotation value, `direction` being the current particle direction
+    #[inline]
+    fn value_3d(&self, direction: impl FnOnce() -> Vec3) -> Quat {
+        match self {
+            Self::FreeRotation3d { rotation, .. } => *rotation,
+            _ => Quat::from_rotation_z(self.value(direction)),
+        }
+    }
+
+    /// Attempts to increase the current `z` rotation by `delta`
+    #[inline]
+    fn try_rotate(&mut self, delta: f32) -> bool {
+        match self {
+            Self::AlignToDirection { .. } => false,
+            Self::FreeRotation { rotation, .. } => {
+                *rotation += delta;
+                true
+            }
+            Self::FreeRotation3d { rotation, .. } => {
+                *rotation *= Quat::from_rotation_z(delta);
+                true
+            }
+        }
+    }
+
+    /// Attempts to increase the current 3D rotation by `delta`, a rotation vector (axis scaled by
+    /// the angle) in the particle local space. 1D rotations only use the `z` axis
+    #[inline]
+    fn try_rotate_3d(&mut self, delta: Vec3) -> bool {
+        match self {
+            Self::FreeRotation3d { rotation, .. } => {
+                *rotation = (*rotation * Quat::from_scaled_axis(delta)).normalize();
+                true
+            }
+            _ => self.try_rotate(delta.z),
+        }
+    }
+
+    /// Attempts to increase the current `z` rotation velocity by `delta`
+    #[inline]
+    fn try_add_angular_velocity(&mut self, delta: f32) -> bool {
+        match self {
+            Self::AlignToDirection { .. } => false,
+            Self::FreeRotation {
+                angular_velocity, ..
+            } => {
+                *angular_velocity += delta;
+                true
+            }
+            Self::FreeRotation3d {
+                angular_velocity, ..
+            } => {
+                angular_velocity.z += delta;
+                true
+            }
+        }
+    }
+
+    /// Attempts to increase the current 3D angular velocity by `delta`. 1D rotations only use the
+    /// `z` axis
+    #[inline]
+    fn try_add_angular_velocity_3d(&mut self, delta: Vec3) -> bool {
+        match self {
+            Self::FreeRotation3d {
+                angular_velocity, ..
+            } => {
+                *angular_velocity += delta;
+                true
+            }
+            _ => self.try_add_angular_velocity(delta.z),
+        }
+    }
+}
+
+impl Default for Particle {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: ParticleRotation::FreeRotation {
+                rotation: 0.0,
+                angular_velocity: 0.0,
+            },
+            size: Vec3::ONE,
+            start_size: Vec3::ONE,
+            lifetime: 1.0,
+            start_lifetime: 1.0,
+            color: Default::default(),
+            velocity: Default::default(),
+            start_direction: Vec3::Y,
+            custom_1: Vec4::ZERO,
+            custom_2: Vec4::ZERO,
+        }
+    }
+}
+
+impl Particle {
+    /// Retrieves the current `z` rotation value of the particle
+    #[must_use]
+    #[inline]
+    pub fn rotation(&self) -> f32 {
+        self.rotation.value(|| self.non_zero_direction())
+    }
+
+    /// Retrieves the current 3D rotation of the particle. 1D rotations are expressed around the
+    /// `z` axis
+    #[must_use]
+    #[inline]
+    pub fn rotation_3d(&self) -> Quat {
+        self.rotation.value_3d(|| self.non_zero_direction())
+    }
+
+    /// Attempts to increase the current `z` rotation by `delta`
+    ///
+    /// # Returns
+    ///
+    /// On success the function returns `true`, on failure it returns `false`
+    /// The function may fail if the particle has its rotation mode set to align to its current
+    /// direction
+    #[inline]
+    pub fn try_rotate(&mut self, delta: f32) -> bool {
+        self.rotation.try_rotate(delta)
+    }
+
+    /// Attempts to increase the current `z` rotation velocity by `delta`
+    ///
+    /// # Returns
+    ///
+    /// On success the function returns `true`, on failure it returns `false`
+    /// The function may fail if the particle has its rotation mode set to align to its current
+    /// direction
+    #[inline]
+    pub fn try_add_angular_velocity(&mut self, delta: f32) -> bool {
+        self.rotation.try_add_angular_velocity(delta)
+    }
+
+    /// Attempts to increase the current 3D rotation by `delta`, a rotation vector (axis scaled by
+    /// the angle) in the particle local space
+    ///
+    /// # Returns
+    ///
+    /// On success the function returns `true`, on failure it returns `false`
+    /// The function may fail if the particle has its rotation mode set to align to its current
+    /// direction. Particles with a 1D rotation only use the `z` axis of `delta`
+    #[inline]
+    pub fn try_rotate_3d(&mut self, delta: Vec3) -> bool {
+        self.rotation.try_rotate_3d(delta)
+    }
+
+    /// Attempts to increase the current 3D angular velocity by `delta`
+    ///
+    /// # Returns
+    ///
+    /// On success the function returns `true`, on failure it returns `false`
+    /// The function may fail if the particle has its rotation mode set to align to its current
+    /// direction. Particles with a 1D rotation only use the `z` axis of `delta`
+    #[inline]
+    pub fn try_add_angular_velocity_3d(&mut self, delta: Vec3) -> bool {
+        self.rotation.try_add_angular_velocity_3d(delta)
+    }
+
+    /// Retrieves the current particle speed, computed from the `length` of its `velocity`
+    #[must_use]
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        self.velocity.length() // TODO optimize with `length_squared`
+    }
+
+    /// How long was the particle alive
+    #[must_use]
+    #[inline]
+    pub fn alive_time(&self) -> f32 {
+        self.start_lifetime - self.lifetime
+    }
+
+    /// How long was the particle alive compared to its original lifetime (between 0 and 1)
+    #[must_use]
+    #[inline]
+    pub fn alive_time_ratio(&self) -> f32 {
+        (self.start_lifetime - self.lifetime) / self.start_lifetime
+    }
+
+    /// returns `self` with its translation transformed by `matrix`
+    #[must_use]
+    #[inline]
+    pub fn transformed(self, matrix: &Mat4) -> Self {
+        Self {
+            translation: matrix.transform_point3(self.translation),
+            velocity: matrix.transform_vector3(self.velocity),
+            start_direction: matrix.transform_vector3(self.start_direction),
+            ..self
+        }
+    }
+
+    /// Retrieves either the current direction from `velocity` or uses the initial direction of the particle
+    #[must_use]
+    #[inline]
+    pub fn non_zero_direction(&self) -> Vec3 {
+        self.velocity
+            .try_normalize()
+            .unwrap_or(self.start_direction)
+    }
+
+    /// Mutable view over the particle, allowing to apply particle modifiers to it
+    #[must_use]
+    #[inline]
+    pub const fn as_particle_mut(&mut self) -> ParticleMut<'_> {
+        ParticleMut {
+            translation: &mut self.translation,
+            rotation: &mut self.rotation,
+            size: &mut self.size,
+            start_size: &mut self.start_size,
+            lifetime: &mut self.lifetime,
+            start_lifetime: &mut self.start_lifetime,
+            color: &mut self.color,
+            velocity: &mut self.velocity,
+            start_direction: &mut self.start_direction,
+            custom_1: &mut self.custom_1,
+            custom_2: &mut self.custom_2,
+        }
+    }
+}
+
+impl ParticleMut<'_> {
+    /// Retrieves the current `z` rotation value of the particle
+    #[must_use]
+    #[inline]
+    pub fn rotation(&self) -> f32 {
+        self.rotation.value(|| self.non_zero_direction())
+    }
+
+    /// Retrieves the current 3D rotation of the particle. 1D rotations are expressed around the
+    /// `z` axis
+    #[must_use]
+    #[inline]
+    pub fn rotation_3d(&self) -> Quat {
+        self.rotation.value_3d(|| self.non_zero_direction())
+    }
+
+    /// Attempts to increase the current `z` rotation by `delta`
+    ///
+    /// # Returns
+    ///
+    /// On success the function returns `true`, on failure it returns `false`
+    /// The function may fail if the particle has its rotation mode set to align to its current
+    /// direction
+    #[inline]
+    pub fn try_rotate(&mut self, delta: f32) -> bool {
+        self.rotation.try_rotate(delta)
+    }
+
+    /// Attempts to increase the current `z` rotation velocity by `delta`
+    ///
+    /// # Returns
+    ///
+    /// On success the function returns `true`, on failure it returns `false`
+    /// The function may fail if the particle has its rotation mode set to align to its current
+    /// direction
+    #[inline]
+    pub fn try_add_angular_velocity(&mut self, delta: f32) -> bool {
+        self.rotation.try_add_angular_velocity(delta)
+    }
+
+    /// Attempts to increase the current 3D rotation by `delta`, a rotation vector (axis scaled by
+    /// the angle) in the particle local space
+    ///
+    /// # Returns
+    ///
+    /// On success the function returns `true`, on failure it returns `false`
+    /// The function may fail if the particle has its rotation mode set to align to its current
+    /// direction. Particles with a 1D rotation only use the `z` axis of `delta`
+    #[inline]
+    pub fn try_rotate_3d(&mut self, delta: Vec3) -> bool {
+        self.rotation.try_rotate_3d(delta)
+    }
+
+    /// Attempts to increase the current 3D angular velocity by `delta`
+    ///
+    /// # Returns
+    ///
+    /// On success the function returns `true`, on failure it returns `false`
+    /// The function may fail if the particle has its rotation mode set to align to its current
+    /// direction. Particles with a 1D rotation only use the `z` axis of `delta`
+    #[inline]
+    pub fn try_add_angular_velocity_3d(&mut self, delta: Vec3) -> bool {
+        self.rotation.try_add_angular_velocity_3d(delta)
+    }
+
+    /// Retrieves the current particle speed, computed from the `length` of its `velocity`
+    #[must_use]
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        self.velocity.length()
+    }
+
+    /// How long was the particle alive
+    #[must_use]
+    #[inline]
+    pub fn alive_time(&self) -> f32 {
+        *self.start_lifetime - *self.lifetime
+    }
+
+    /// How long was the particle alive compared to its original lifetime (between 0 and 1)
+    #[must_use]
+    #[inline]
+    pub fn alive_time_ratio(&self) -> f32 {
+        (*self.start_lifetime - *self.lifetime) / *self.start_lifetime
+    }
+
+    /// Retrieves either the current direction from `velocity` or uses the initial direction of the particle
+    #[must_use]
+    #[inline]
+    pub fn non_zero_direction(&self) -> Vec3 {
+        self.velocity
+            .try_normalize()
+            .unwrap_or(*self.start_direction)
+    }
+
+    /// Copies the particle attributes
+    #[must_use]
+    pub fn to_particle(&self) -> Particle {
+        Particle {
+            translation: *self.translation,
+            rotation: self.rotation.clone(),
+            size: *self.size,
+            start_size: *self.start_size,
+            lifetime: *self.lifetime,
+            start_lifetime: *self.start_lifetime,
+            color: *self.color,
+            velocity: *self.velocity,
+            start_direction: *self.start_direction,
+            custom_1: *self.custom_1,
+            custom_2: *self.custom_2,
+        }
+    }
+
+    /// Overwrites every particle attribute with `particle`
+    pub const fn set(&mut self, particle: Particle) {
+        *self.translation = particle.translation;
+        *self.rotation = particle.rotation;
+        *self.size = particle.size;
+        *self.start_size = particle.start_size;
+        *self.lifetime = particle.lifetime;
+        *self.start_lifetime = particle.start_lifetime;
+        *self.color = particle.color;
+        *self.velocity = particle.velocity;
+        *self.start_direction = particle.start_direction;
+        *self.custom_1 = particle.custom_1;
+        *self.custom_2 = particle.custom_2;
+    }
+
+    #[inline]
+    pub(crate) fn update(&mut self, delta_time: f32) {
+        *self.lifetime -= delta_time;
+        *self.translation += *self.velocity * delta_time;
+        match self.rotation {
+            ParticleRotation::AlignToDirection { .. } => (),
+            ParticleRotation::FreeRotation {
+                rotation,
+                angular_velocity,
+            } => *rotation += *angular_velocity * delta_time,
+            ParticleRotation::FreeRotation3d {
+                rotation,
+                angular_velocity,
+            } => {
+                *rotation = (*rotation * Quat::from_scaled_axis(*angular_velocity * delta_time))
+                    .normalize();
+            }
+        }
+    }
+}
+
+impl FromReflect for Particle {
+    fn from_reflect(reflect: &dyn Reflect) -> Option<Self> {
+        reflect.any().downcast_ref::<Self>().cloned()
+    }
+}
@@ -0,0 +1,513 @@
+use crate::particle::{Particle, ParticleMut, ParticleRotation, TrailPoint};
+use bevy::prelude::{Color, Mat4, Vec3, Vec4};
+use itertools::izip;
+use std::collections::VecDeque;
+
+/// Structure of arrays particle storage: every particle attribute is stored in a separate array,
+/// the particle at `index` being made of the attributes at `index` of every array.
+///
+/// Particle modifiers only stream the attributes they access, and removing a particle is a
+/// `swap_remove` on every array, so the particles order is not preserved
+#[derive(Debug, Clone, Default)]
+pub struct ParticleStorage {
+    translations: Vec<Vec3>,
+    rotations: Vec<ParticleRotation>,
+    sizes: Vec<Vec3>,
+    start_sizes: Vec<Vec3>,
+    lifetimes: Vec<f32>,
+    start_lifetimes: Vec<f32>,
+    colors: Vec<Color>,
+    velocities: Vec<Vec3>,
+    start_directions: Vec<Vec3>,
+    customs_1: Vec<Vec4>,
+    customs_2: Vec<Vec4>,
+    emission_indices: Vec<u64>,
+    trails: Vec<VecDeque<TrailPoint>>,
+    /// Amount of particles ever pushed in the storage
+    emitted_count: u64,
+}
+
+/// Mutable access to a range of particles of a [`ParticleStorage`], which can be split to process
+/// the particles in parallel
+#[derive(Debug)]
+pub struct ParticleSliceMut<'a> {
+    translations: &'a mut [Vec3],
+    rotations: &'a mut [ParticleRotation],
+    sizes: &'a mut [Vec3],
+    start_sizes: &'a mut [Vec3],
+    lifetimes: &'a mut [f32],
+    start_lifetimes: &'a mut [f32],
+    colors: &'a mut [Color],
+    velocities: &'a mut [Vec3],
+    start_directions: &'a mut [Vec3],
+    customs_1: &'a mut [Vec4],
+    customs_2: &'a mut [Vec4],
+}
+
+impl ParticleStorage {
+    /// Amount of stored particles
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.lifetimes.len()
+    }
+
+    /// Is the storage empty
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.lifetimes.is_empty()
+    }
+
+    /// Copies the particle at `index`, if any
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<Particle> {
+        (index < self.len()).then(|| self.particle(index))
+    }
+
+    /// Iterates over copies of every particle
+    #[must_use]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Particle> + '_ {
+        (0..self.len()).map(|index| self.particle(index))
+    }
+
+    /// Every particle translation
+    #[must_use]
+    #[inline]
+    pub fn translations(&self) -> &[Vec3] {
+        &self.translations
+    }
+
+    /// Every particle size
+    #[must_use]
+    #[inline]
+    pub fn sizes(&self) -> &[Vec3] {
+        &self.sizes
+    }
+
+    /// Every particle start size
+    #[must_use]
+    #[inline]
+    pub fn start_sizes(&self) -> &[Vec3] {
+        &self.start_sizes
+    }
+
+    /// Every particle remaining lifetime
+    #[must_use]
+    #[inline]
+    pub fn lifetimes(&self) -> &[f32] {
+        &self.lifetimes
+    }
+
+    /// Every particle start lifetime
+    #[must_use]
+    #[inline]
+    pub fn start_lifetimes(&self) -> &[f32] {
+        &self.start_lifetimes
+    }
+
+    /// Every particle color
+    #[must_use]
+    #[inline]
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Every particle velocity
+    #[must_use]
+    #[inline]
+    pub fn velocities(&self) -> &[Vec3] {
+        &self.velocities
+    }
+
+    /// Every particle first custom data slot
+    #[must_use]
+    #[inline]
+    pub fn customs_1(&self) -> &[Vec4] {
+        &self.customs_1
+    }
+
+    /// Every particle second custom data slot
+    #[must_use]
+    #[inline]
+    pub fn customs_2(&self) -> &[Vec4] {
+        &self.customs_2
+    }
+
+    /// Every particle emission index, increasing in emission order
+    #[must_use]
+    #[inline]
+    pub fn emission_indices(&self) -> &[u64] {
+        &self.emission_indices
+    }
+
+    /// Every particle trail, from the oldest to the newest recorded position. The trails are only
+    /// recorded for particle systems with [`ParticleTrails`](crate::ParticleTrails)
+    #[must_use]
+    #[inline]
+    pub fn trails(&self) -> &[VecDeque<TrailPoint>] {
+        &self.trails
+    }
+
+    /// Ages every trail point by `delta_time`, removes the points older than `lifetime` and
+    /// records the particles position if they moved at least `minimum_distance` from their last
+    /// recorded position
+    pub(crate) fn update_trails(&mut self, delta_time: f32, lifetime: f32, minimum_distance: f32) {
+        for (translation, trail) in self.translations.iter().zip(&mut self.trails) {
+            for point in trail.iter_mut() {
+                point.age += delta_time;
+            }
+            while trail.front().is_some_and(|p| p.age > lifetime) {
+                trail.pop_front();
+            }
+            if !trail.back().is_some_and(|p| {
+                p.position.distance_squared(*translation) < minimum_distance * minimum_distance
+            }) {
+                trail.push_back(TrailPoint {
+                    position: *translation,
+                    age: 0.0,
+                });
+            }
+        }
+    }
+
+    /// Transforms every recorded trail point by `matrix`
+    pub(crate) fn transform_trails(&mut self, matrix: &Mat4) {
+        for point in self.trails.iter_mut().flatten() {
+            point.position = matrix.transform_point3(point.position);
+        }
+    }
+
+    /// Mutable access to every particle
+    pub(crate) fn as_slice_mut(&mut self) -> ParticleSliceMut<'_> {
+        ParticleSliceMut {
+            translations: &mut self.translations,
+            rotations: &mut self.rotations,
+            sizes: &mut self.sizes,
+            start_sizes: &mut self.start_sizes,
+            lifetimes: &mut self.lifetimes,
+            start_lifetimes: &mut self.start_lifetimes,
+            colors: &mut self.colors,
+            velocities: &mut self.velocities,
+            start_directions: &mut self.start_directions,
+            customs_1: &mut self.customs_1,
+            customs_2: &mut self.customs_2,
+        }
+    }
+
+    /// Adds a particle at the end of the storage
+    pub(crate) fn push(&mut self, particle: Particle) {
+        self.translations.push(particle.translation);
+        self.rotations.push(particle.rotation);
+        self.sizes.push(particle.size);
+        self.start_sizes.push(particle.start_size);
+        self.lifetimes.push(particle.lifetime);
+        self.start_lifetimes.push(particle.start_lifetime);
+        self.colors.push(particle.color);
+        self.velocities.push(particle.velocity);
+        self.start_directions.push(particle.start_direction);
+        self.customs_1.push(particle.custom_1);
+        self.customs_2.push(particle.custom_2);
+        self.emission_indices.push(self.emitted_count);
+        self.trails.push(VecDeque::new());
+        self.emitted_count += 1;
+    }
+
+    /// Removes the particle at `index`, replacing it with the last particle
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub(crate) fn swap_remove(&mut self, index: usize) -> Particle {
+        self.emission_indices.swap_remove(index);
+        self.trails.swap_remove(index);
+        Particle {
+            translation: self.translations.swap_remove(index),
+            rotation: self.rotations.swap_remove(index),
+            size: self.sizes.swap_remove(index),
+            start_size: self.start_sizes.swap_remove(index),
+            lifetime: self.lifetimes.swap_remove(index),
+            start_lifetime: self.start_lifetimes.swap_remove(index),
+            color: self.colors.swap_remove(index),
+            velocity: self.velocities.swap_remove(index),
+            start_direction: self.start_directions.swap_remove(index),
+            custom_1: self.customs_1.swap_remove(index),
+            custom_2: self.customs_2.swap_remove(index),
+        }
+    }
+
+    /// Removes every particle whose lifetime is over, pushing them in `dead_particles`
+    pub(crate) fn remove_dead(&mut self, dead_particles: &mut Vec<Particle>) {
+        let mut index = 0;
+        while index < self.len() {
+            if self.lifetimes[index] > 0.0 {
+                index += 1;
+            } else {
+                dead_particles.push(self.swap_remove(index));
+            }
+        }
+    }
+
+    /// Removes the `count` oldest particles, the ones with the smallest emission indices, pushing
+    /// them in `dead_particles`
+    pub(crate) fn remove_oldest(&mut self, count: usize, dead_particles: &mut Vec<Particle>) {
+        if count == 0 {
+            return;
+        }
+        if count >= self.len() {
+            dead_particles.extend(self.iter());
+            self.clear();
+            return;
+        }
+        let mut indices = self.emission_indices.clone();
+        let (_, &mut newest_removed, _) = indices.select_nth_unstable(count - 1);
+        let mut index = 0;
+        while index < self.len() {
+            if self.emission_indices[index] > newest_removed {
+                index += 1;
+            } else {
+                dead_particles.push(self.swap_remove(index));
+            }
+        }
+    }
+
+    /// Removes every particle
+    pub(crate) fn clear(&mut self) {
+        self.translations.clear();
+        self.rotations.clear();
+        self.sizes.clear();
+        self.start_sizes.clear();
+        self.lifetimes.clear();
+        self.start_lifetimes.clear();
+        self.colors.clear();
+        self.velocities.clear();
+        self.start_directions.clear();
+        self.customs_1.clear();
+        self.customs_2.clear();
+        self.emission_indices.clear();
+        self.trails.clear();
+    }
+
+    fn particle(&self, index: usize) -> Particle {
+        Particle {
+            translation: self.translations[index],
+            rotation: self.rotations[index].clone(),
+            size: self.sizes[index],
+            start_size: self.start_sizes[index],
+            lifetime: self.lifetimes[index],
+            start_lifetime: self.start_lifetimes[index],
+            color: self.colors[index],
+            velocity: self.velocities[index],
+            start_direction: self.start_directions[index],
+            custom_1: self.customs_1[index],
+            custom_2: self.customs_2[index],
+        }
+    }
+}
+
+impl<'a> ParticleSliceMut<'a> {
+    /// Amount of particles in the slice
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.lifetimes.len()
+    }
+
+    /// Is the slice empty
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.lifetimes.is_empty()
+    }
+
+    /// Iterates over mutable views of every particle of the slice
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ParticleMut<'_>> {
+        izip!(
+            self.translations.iter_mut(),
+            self.rotations.iter_mut(),
+            self.sizes.iter_mut(),
+            self.start_sizes.iter_mut(),
+            self.lifetimes.iter_mut(),
+            self.start_lifetimes.iter_mut(),
+            self.colors.iter_mut(),
+            self.velocities.iter_mut(),
+            self.start_directions.iter_mut(),
+            self.customs_1.iter_mut(),
+            self.customs_2.iter_mut(),
+        )
+        .map(
+            |(
+                translation,
+                rotation,
+                size,
+                start_size,
+                lifetime,
+                start_lifetime,
+                color,
+                velocity,
+                start_direction,
+                custom_1,
+                custom_2,
+            )| ParticleMut {
+                translation,
+                rotation,
+                size,
+                start_size,
+                lifetime,
+                start_lifetime,
+                color,
+                velocity,
+                start_direction,
+                custom_1,
+                custom_2,
+            },
+        )
+    }
+
+    /// Divides the slice into two at `mid`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`
+    #[must_use]
+    pub const fn split_at(self, mid: usize) -> (Self, Self) {
+        let (translations, translations_end) = self.translations.split_at_mut(mid);
+        let (rotations, rotations_end) = self.rotations.split_at_mut(mid);
+        let (sizes, sizes_end) = self.sizes.split_at_mut(mid);
+        let (start_sizes, start_sizes_end) = self.start_sizes.split_at_mut(mid);
+        let (lifetimes, lifetimes_end) = self.lifetimes.split_at_mut(mid);
+        let (start_lifetimes, start_lifetimes_end) = self.start_lifetimes.split_at_mut(mid);
+        let (colors, colors_end) = self.colors.split_at_mut(mid);
+        let (velocities, velocities_end) = self.velocities.split_at_mut(mid);
+        let (start_directions, start_directions_end) = self.start_directions.split_at_mut(mid);
+        let (customs_1, customs_1_end) = self.customs_1.split_at_mut(mid);
+        let (customs_2, customs_2_end) = self.customs_2.split_at_mut(mid);
+        (
+            Self {
+                translations,
+                rotations,
+                sizes,
+                start_sizes,
+                lifetimes,
+                start_lifetimes,
+                colors,
+                velocities,
+                start_directions,
+                customs_1,
+                customs_2,
+            },
+            Self {
+                translations: translations_end,
+                rotations: rotations_end,
+                sizes: sizes_end,
+                start_sizes: start_sizes_end,
+                lifetimes: lifetimes_end,
+                start_lifetimes: start_lifetimes_end,
+                colors: colors_end,
+                velocities: velocities_end,
+                start_directions: start_directions_end,
+                customs_1: customs_1_end,
+                customs_2: customs_2_end,
+            },
+        )
+    }
+
+    /// Divides the slice into chunks of `chunk_size` particles, the last chunk may be shorter
+    pub fn into_chunks(self, chunk_size: usize) -> impl Iterator<Item = ParticleSliceMut<'a>> {
+        let mut remaining = (!self.is_empty()).then_some(self);
+        std::iter::from_fn(move || {
+            let slice = remaining.take()?;
+            if slice.len() <= chunk_size {
+                return Some(slice);
+            }
+            let (chunk, rest) = slice.split_at(chunk_size);
+            remaining = Some(rest);
+            Some(chunk)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Particle whose attributes all derive from `value`
+    fn particle(value: usize, lifetime: f32) -> Particle {
+        let value = value as f32;
+        Particle {
+            translation: Vec3::splat(value),
+            size: Vec3::splat(value + 1.0),
+            lifetime,
+            velocity: Vec3::X * value,
+            custom_1: Vec4::splat(value),
+            ..Default::default()
+        }
+    }
+
+    /// Storage of `count` particles, every third particle being dead
+    fn storage(count: usize) -> ParticleStorage {
+        let mut storage = ParticleStorage::default();
+        for value in 0..count {
+            storage.push(particle(value, if value % 3 == 0 { 0.0 } else { 1.0 }));
+        }
+        storage
+    }
+
+    /// Asserts every column of `storage` still describes the particle of its emission index
+    fn assert_aligned(storage: &ParticleStorage) {
+        for (index, particle) in storage.iter().enumerate() {
+            let value = storage.emission_indices()[index] as f32;
+            assert_eq!(particle.translation, Vec3::splat(value));
+            assert_eq!(particle.size, Vec3::splat(value + 1.0));
+            assert_eq!(particle.velocity, Vec3::X * value);
+            assert_eq!(particle.custom_1, Vec4::splat(value));
+        }
+    }
+
+    #[test]
+    fn remove_dead_keeps_columns_aligned() {
+        let mut storage = storage(10);
+        let mut dead_particles = Vec::new();
+        storage.remove_dead(&mut dead_particles);
+        assert_eq!(storage.len(), 6);
+        assert!(storage.lifetimes().iter().all(|lifetime| *lifetime > 0.0));
+        assert_aligned(&storage);
+        let mut dead: Vec<_> = dead_particles.iter().map(|p| p.translation.x).collect();
+        dead.sort_by(f32::total_cmp);
+        assert_eq!(dead, [0.0, 3.0, 6.0, 9.0]);
+    }
+
+    #[test]
+    fn remove_oldest_keeps_the_newest_particles() {
+        let mut storage = storage(10);
+        // Shuffles the particles so the storage order is not the emission order
+        storage.remove_dead(&mut Vec::new());
+        let mut dead_particles = Vec::new();
+        storage.remove_oldest(2, &mut dead_particles);
+        let mut indices = storage.emission_indices().to_vec();
+        indices.sort_unstable();
+        assert_eq!(indices, [4, 5, 7, 8]);
+        assert_aligned(&storage);
+        let mut dead: Vec<_> = dead_particles.iter().map(|p| p.translation.x).collect();
+        dead.sort_by(f32::total_cmp);
+        assert_eq!(dead, [1.0, 2.0]);
+
+        storage.remove_oldest(10, &mut dead_particles);
+        assert!(storage.is_empty());
+        assert!(storage.emission_indices().is_empty());
+        assert_eq!(dead_particles.len(), 6);
+    }
+
+    #[test]
+    fn trails_are_removed_with_their_particle() {
+        let mut storage = storage(10);
+        storage.update_trails(0.1, 1.0, 0.0);
+        storage.remove_dead(&mut Vec::new());
+        storage.remove_oldest(2, &mut Vec::new());
+        assert_eq!(storage.trails().len(), storage.len());
+        for (trail, translation) in storage.trails().iter().zip(storage.translations()) {
+            assert_eq!(trail.len(), 1);
+            assert_eq!(trail[0].position, *translation);
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use crate::render::draw::{
+    DrawParticleBatch, DrawParticleMeshBatch, SetParticleBatchBindGroup,
+    SetParticleMeshBatchBindGroup, SetParticleMeshTextureBindGroup, SetParticleTextureBindGroup,
+    SetParticleViewBindGroup,
+};
+use crate::render::pipeline::{ParticleMeshPipeline, ParticlePipeline};
+use crate::render::queue::{add_particle_batch, draw_function_ids, prepare_image_bind_group};
+use crate::render::{
+    ExtractedCustomMaterials, ParticleBatch, ParticleImageBindGroups, ParticleMeshBatch,
+    ParticleMeta,
+};
+use crate::{ParticleBlendMode, ParticleLabel, ParticleSystem};
+use bevy::asset::{Asset, HandleId};
+use bevy::core_pipeline::{AlphaMask3d, Transparent3d};
+use bevy::ecs::system::{
+    lifetimeless::{Read, SQuery, SRes},
+    SystemParamItem,
+};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::render::mesh::MeshVertexBufferLayout;
+use bevy::render::render_asset::{RenderAsset, RenderAssetPlugin, RenderAssets};
+use bevy::render::render_phase::{
+    AddRenderCommand, DrawFunctions, EntityRenderCommand, RenderCommandResult, RenderPhase,
+    SetItemPipeline, TrackedRenderPass,
+};
+use bevy::render::render_resource::{
+    BindGroup, BindGroupLayout, PipelineCache, PrimitiveTopology, RenderPipelineDescriptor,
+    SpecializedMeshPipeline, SpecializedMeshPipelineError, SpecializedMeshPipelines,
+    SpecializedRenderPipeline, SpecializedRenderPipelines,
+};
+use bevy::render::renderer::RenderDevice;
+use bevy::render::{RenderApp, RenderStage, RenderWorld};
+use std::marker::PhantomData;
+
+/// Particle materials with custom shaders and bind group, used alongside the
+/// [`CustomParticleMaterialPlugin`] by adding a `Handle<M>` component to a particle system.
+///
+/// The particles keep using the [`ParticleMaterial`](crate::ParticleMaterial) texture and blend mode
+/// and are extracted, sorted and batched like any other particle. The custom shaders can import
+/// the `bevy_tickles::particle_types` definitions and bindings:
+///
+/// | Group | Bindings |
+/// |-------|----------|
+/// | `0` | `view` |
+/// | `1` | `sprite_texture` and `sprite_sampler` |
+/// | `2` | `batch` (the `ParticleBatch` uniform) |
+/// | `3` | The custom material bind group |
+pub trait CustomParticleMaterial: Asset + RenderAsset + Sized {
+    /// Returns this material's [`BindGroup`]. This should match the layout returned by
+    /// [`CustomParticleMaterial::bind_group_layout`].
+    fn bind_group(material: &<Self as RenderAsset>::PreparedAsset) -> &BindGroup;
+
+    /// Returns this material's [`BindGroupLayout`]. This should match the [`BindGroup`] returned
+    /// by [`CustomParticleMaterial::bind_group`].
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout;
+
+    /// Returns this material's vertex shader for billboards and trails. If [`None`] is returned,
+    /// the default particle vertex shader will be used.
+    /// Defaults to [`None`].
+    #[must_use]
+    #[allow(unused_variables)]
+    fn vertex_shader(asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        None
+    }
+
+    /// Returns this material's vertex shader for instanced mesh particles. If [`None`] is returned,
+    /// the default particle mesh vertex shader will be used.
+    /// Defaults to [`None`].
+    #[must_use]
+    #[allow(unused_variables)]
+    fn mesh_vertex_shader(asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        None
+    }
+
+    /// Returns this material's fragment shader. If [`None`] is returned, the default particle
+    /// fragment shader will be used.
+    /// Defaults to [`None`].
+    #[must_use]
+    #[allow(unused_variables)]
+    fn fragment_shader(asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        None
+    }
+
+    /// The dynamic uniform indices to set for the given `material`'s [`BindGroup`].
+    /// Defaults to an empty array / no dynamic uniform indices.
+    #[allow(unused_variables)]
+    #[inline]
+    fn dynamic_uniform_indices(material: &<Self as RenderAsset>::PreparedAsset) -> &[u32] {
+        &[]
+    }
+
+    /// Customizes the default [`RenderPipelineDescriptor`] of the particles rendered with
+    /// `blend_mode`, which already holds the blend mode shader defs
+    #[allow(unused_variables)]
+    #[inline]
+    fn specialize(descriptor: &mut RenderPipelineDescriptor, blend_mode: ParticleBlendMode) {}
+}
+
+/// Adds the necessary ECS resources and render logic to render particle systems with the given
+/// [`CustomParticleMaterial`] asset type.
+///
+/// Note: This plugin must be added after the [`ParticlesPlugin`](crate::ParticlesPlugin)
+pub struct CustomParticleMaterialPlugin<M: CustomParticleMaterial>(PhantomData<M>);
+
+impl<M: CustomParticleMaterial> Default for CustomParticleMaterialPlugin<M> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<M: CustomParticleMaterial> Plugin for CustomParticleMaterialPlugin<M> {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<M>()
+            .add_plugin(RenderAssetPlugin::<M>::default());
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .add_render_command::<Transparent3d, DrawCustomParticle<M>>()
+                .add_render_command::<Transparent3d, DrawCustomParticleMesh<M>>()
+                .add_render_command::<AlphaMask3d, DrawCustomParticle<M>>()
+                .add_render_command::<AlphaMask3d, DrawCustomParticleMesh<M>>()
+                .init_resource::<CustomParticlePipeline<M>>()
+                .init_resource::<SpecializedRenderPipelines<CustomParticlePipeline<M>>>()
+                .init_resource::<SpecializedMeshPipelines<CustomParticlePipeline<M>>>()
+                .add_system_to_stage(
+                    RenderStage::Extract,
+                    extract_custom_materials::<M>.before(ParticleLabel::ExtractParticles),
+                )
+                .add_system_to_stage(
+                    RenderStage::Queue,
+                    queue_custom_particles::<M>.after(ParticleLabel::QueueParticles),
+                );
+        }
+    }
+}
+
+/// Particle pipeline using the shaders and bind group layout of a [`CustomParticleMaterial`]
+pub struct CustomParticlePipeline<M: CustomParticleMaterial> {
+    particle_pipeline: ParticlePipeline,
+    mesh_pipeline: ParticleMeshPipeline,
+    /// Layout of the custom material bind group, at index `3`
+    pub material_layout: BindGroupLayout,
+    /// Custom billboard and trail vertex shader
+    pub vertex_shader: Option<Handle<Shader>>,
+    /// Custom instanced mesh vertex shader
+    pub mesh_vertex_shader: Option<Handle<Shader>>,
+    /// Custom fragment shader
+    pub fragment_shader: Option<Handle<Shader>>,
+    marker: PhantomData<M>,
+}
+
+impl<M: CustomParticleMaterial> CustomParticlePipeline<M> {
+    /// Applies the custom shaders and bind group layout to the default particle `descriptor`
+    fn customize(
+        &self,
+        descriptor: &mut RenderPipelineDescriptor,
+        vertex_shader: Option<&Handle<Shader>>,
+        blend_mode: ParticleBlendMode,
+    ) {
+        if let Some(vertex_shader) = vertex_shader {
+            descriptor.vertex.shader = vertex_shader.clone();
+        }
+        if let (Some(fragment), Some(fragment_shader)) =
+            (descriptor.fragment.as_mut(), &self.fragment_shader)
+        {
+            fragment.shader = fragment_shader.clone();
+        }
+        if let Some(layout) = descriptor.layout.as_mut() {
+            layout.push(self.material_layout.clone());
+        }
+        M::specialize(descriptor, blend_mode);
+    }
+}
+
+impl<M: CustomParticleMaterial> SpecializedRenderPipeline for CustomParticlePipeline<M> {
+    /// The particles blend mode
+    type Key = ParticleBlendMode;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut descriptor = self.particle_pipeline.specialize(key);
+        self.customize(&mut descriptor, self.vertex_shader.as_ref(), key);
+        descriptor
+    }
+}
+
+impl<M: CustomParticleMaterial> SpecializedMeshPipeline for CustomParticlePipeline<M> {
+    /// The mesh primitive topology and the particles blend mode
+    type Key = (PrimitiveTopology, ParticleBlendMode);
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.mesh_pipeline.specialize(key, layout)?;
+        self.customize(&mut descriptor, self.mesh_vertex_shader.as_ref(), key.1);
+        Ok(descriptor)
+    }
+}
+
+impl<M: CustomParticleMaterial> FromWorld for CustomParticlePipeline<M> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        let render_device = world.get_resource::<RenderDevice>().unwrap();
+        let particle_pipeline = world
+            .get_resource::<ParticlePipeline>()
+            .expect("`CustomParticleMaterialPlugin` must be added after the `ParticlesPlugin`");
+        let mesh_pipeline = world.get_resource::<ParticleMeshPipeline>().unwrap();
+        Self {
+            particle_pipeline: particle_pipeline.clone(),
+            mesh_pipeline: mesh_pipeline.clone(),
+            material_layout: M::bind_group_layout(render_device),
+            vertex_shader: M::vertex_shader(asset_server),
+            mesh_vertex_shader: M::mesh_vertex_shader(asset_server),
+            fragment_shader: M::fragment_shader(asset_server),
+            marker: PhantomData,
+        }
+    }
+}
+
+type DrawCustomParticle<M> = (
+    SetItemPipeline,
+    SetParticleViewBindGroup<0>,
+    SetParticleTextureBindGroup<1>,
+    SetParticleBatchBindGroup<2>,
+    SetCustomParticleMaterialBindGroup<M, 3>,
+    DrawParticleBatch,
+);
+
+type DrawCustomParticleMesh<M> = (
+    SetItemPipeline,
+    SetParticleViewBindGroup<0>,
+    SetParticleMeshTextureBindGroup<1>,
+    SetParticleMeshBatchBindGroup<2>,
+    SetCustomParticleMeshMaterialBindGroup<M, 3>,
+    DrawParticleMeshBatch,
+);
+
+/// Sets the custom material bind group of a [`ParticleBatch`]
+pub struct SetCustomParticleMaterialBindGroup<M: CustomParticleMaterial, const I: usize>(
+    PhantomData<M>,
+);
+impl<M: CustomParticleMaterial, const I: usize> EntityRenderCommand
+    for SetCustomParticleMaterialBindGroup<M, I>
+{
+    type Param = (SRes<RenderAssets<M>>, SQuery<Read<ParticleBatch>>);
+
+    fn render<'w>(
+        _view: Entity,
+        item: Entity,
+        (materials, query_batch): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let batch = query_batch.get(item).unwrap();
+        let Some(material) = batch
+            .custom_material
+            .and_then(|id| materials.into_inner().get(&Handle::weak(id)))
+        else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(
+            I,
+            M::bind_group(material),
+            M::dynamic_uniform_indices(material),
+        );
+        RenderCommandResult::Success
+    }
+}
+
+/// Sets the custom material bind group of a [`ParticleMeshBatch`]
+pub struct SetCustomParticleMeshMaterialBindGroup<M: CustomParticleMaterial, const I: usize>(
+    PhantomData<M>,
+);
+impl<M: CustomParticleMaterial, const I: usize> EntityRenderCommand
+    for SetCustomParticleMeshMaterialBindGroup<M, I>
+{
+    type Param = (SRes<RenderAssets<M>>, SQuery<Read<ParticleMeshBatch>>);
+
+    fn render<'w>(
+        _view: Entity,
+        item: Entity,
+        (materials, query_batch): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let batch = query_batch.get(item).unwrap();
+        let Some(material) = batch
+            .custom_material
+            .and_then(|id| materials.into_inner().get(&Handle::weak(id)))
+        else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(
+            I,
+            M::bind_group(material),
+            M::dynamic_uniform_indices(material),
+        );
+        RenderCommandResult::Success
+    }
+}
+
+/// Extracts the `Handle<M>` of the particle systems, before the particles are extracted
+#[allow(clippy::needless_pass_by_value)]
+pub fn extract_custom_materials<M: CustomParticleMaterial>(
+    mut render_world: ResMut<RenderWorld>,
+    query: Query<(Entity, &Handle<M>), With<ParticleSystem>>,
+) {
+    let mut custom_materials = render_world
+        .get_resource_mut::<ExtractedCustomMaterials>()
+        .unwrap();
+    custom_materials
+        .values
+        .extend(query.iter().map(|(entity, handle)| (entity, handle.id)));
+}
+
+/// Queues the particle batches using a prepared `M` material
+#[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+pub fn queue_custom_particles<M: CustomParticleMaterial>(
+    (transparent_draw_functions, alpha_mask_draw_functions): (
+        Res<DrawFunctions<Transparent3d>>,
+        Res<DrawFunctions<AlphaMask3d>>,
+    ),
+    render_device: Res<RenderDevice>,
+    custom_pipeline: Res<CustomParticlePipeline<M>>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<CustomParticlePipeline<M>>>,
+    mut mesh_pipelines: ResMut<SpecializedMeshPipelines<CustomParticlePipeline<M>>>,
+    mut pipeline_cache: ResMut<PipelineCache>,
+    particle_meta: Res<ParticleMeta>,
+    render_materials: Res<RenderAssets<M>>,
+    gpu_images: Res<RenderAssets<Image>>,
+    gpu_meshes: Res<RenderAssets<Mesh>>,
+    batch_query: Query<(Entity, &ParticleBatch)>,
+    mesh_batch_query: Query<(Entity, &ParticleMeshBatch)>,
+    mut image_bind_groups: ResMut<ParticleImageBindGroups>,
+    mut views: Query<(
+        &mut RenderPhase<Transparent3d>,
+        &mut RenderPhase<AlphaMask3d>,
+    )>,
+) {
+    // The view bind group is defined by the main particle queue system
+    if particle_meta.view_bind_group.is_none() {
+        return;
+    }
+    let is_prepared = |custom_material: Option<HandleId>| {
+        custom_material.is_some_and(|id| render_materials.get(&Handle::weak(id)).is_some())
+    };
+    let image_layout = &custom_pipeline.particle_pipeline.image_layout;
+    let draw_particle_function = draw_function_ids::<DrawCustomParticle<M>>(
+        &transparent_draw_functions,
+        &alpha_mask_draw_functions,
+    );
+    let draw_particle_mesh_function = draw_function_ids::<DrawCustomParticleMesh<M>>(
+        &transparent_draw_functions,
+        &alpha_mask_draw_functions,
+    );
+
+    for (mut transparent_phase, mut alpha_mask_phase) in views.iter_mut() {
+        for (entity, batch) in batch_query
+            .iter()
+            .filter(|(_, b)| is_prepared(b.custom_material))
+        {
+            let pipeline =
+                pipelines.specialize(&mut pipeline_cache, &custom_pipeline, batch.blend_mode);
+            if prepare_image_bind_group(
+                batch.image_handle_id,
+                &mut image_bind_groups,
+                &gpu_images,
+                &render_device,
+                image_layout,
+            ) {
+                add_particle_batch(
+                    (&mut transparent_phase, &mut alpha_mask_phase),
+                    draw_particle_function,
+                    batch.blend_mode,
+                    pipeline,
+                    entity,
+                );
+            }
+        }
+        for (entity, batch) in mesh_batch_query
+            .iter()
+            .filter(|(_, b)| is_prepared(b.custom_material))
+        {
+            let Some(gpu_mesh) = gpu_meshes.get(&Handle::weak(batch.mesh_handle_id)) else {
+                continue;
+            };
+            let pipeline = match mesh_pipelines.specialize(
+                &mut pipeline_cache,
+                &custom_pipeline,
+                (gpu_mesh.primitive_topology, batch.blend_mode),
+                &gpu_mesh.layout,
+            ) {
+                Ok(pipeline) => pipeline,
+                Err(err) => {
+                    log::error!("Failed to specialize the custom particle pipeline: {}", err);
+                    continue;
+                }
+            };
+            if prepare_image_bind_group(
+                batch.image_handle_id,
+                &mut image_bind_groups,
+                &gpu_images,
+                &render_device,
+                image_layout,
+            ) {
+                add_particle_batch(
+                    (&mut transparent_phase, &mut alpha_mask_phase),
+                    draw_particle_mesh_function,
+                    batch.blend_mode,
+                    pipeline,
+                    entity,
+                );
+            }
+        }
+    }
+}
@@ -1,10 +1,12 @@
-use crate::render::{ParticleBatch, ParticleMeta};
+use crate::render::{ParticleBatch, ParticleMeshBatch, ParticleMeta};
 use crate::ParticleImageBindGroups;
 use bevy::ecs::system::{
     lifetimeless::{Read, SQuery, SRes},
     SystemParamItem,
 };
 use bevy::prelude::*;
+use bevy::render::mesh::GpuBufferInfo;
+use bevy::render::render_asset::RenderAssets;
 use bevy::render::render_phase::{
     EntityRenderCommand, RenderCommandResult, SetItemPipeline, TrackedRenderPass,
 };
@@ -14,9 +16,18 @@ pub type DrawParticle = (
     SetItemPipeline,
     SetParticleViewBindGroup<0>,
     SetParticleTextureBindGroup<1>,
+    SetParticleBatchBindGroup<2>,
     DrawParticleBatch,
 );
 
+pub type DrawParticleMesh = (
+    SetItemPipeline,
+    SetParticleViewBindGroup<0>,
+    SetParticleMeshTextureBindGroup<1>,
+    SetParticleMeshBatchBindGroup<2>,
+    DrawParticleMeshBatch,
+);
+
 pub struct SetParticleViewBindGroup<const I: usize>;
 impl<const I: usize> EntityRenderCommand for SetParticleViewBindGroup<I> {
     type Param = (SRes<ParticleMeta>, SQuery<Read<ViewUniformOffset>>);
@@ -62,6 +73,26 @@ impl<const I: usize> EntityRenderCommand for SetParticleTextureBindGroup<I> {
     }
 }
 
+pub struct SetParticleBatchBindGroup<const I: usize>;
+impl<const I: usize> EntityRenderCommand for SetParticleBatchBindGroup<I> {
+    type Param = (SRes<ParticleMeta>, SQuery<Read<ParticleBatch>>);
+
+    fn render<'w>(
+        _view: Entity,
+        item: Entity,
+        (meta, query_batch): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let batch = query_batch.get(item).unwrap();
+        pass.set_bind_group(
+            I,
+            meta.into_inner().batch_bind_group.as_ref().unwrap(),
+            &[batch.uniform_offset],
+        );
+        RenderCommandResult::Success
+    }
+}
+
 pub struct DrawParticleBatch;
 impl EntityRenderCommand for DrawParticleBatch {
     type Param = (SRes<ParticleMeta>, SQuery<Read<ParticleBatch>>);
@@ -82,3 +113,90 @@ impl EntityRenderCommand for DrawParticleBatch {
         RenderCommandResult::Success
     }
 }
+
+pub struct SetParticleMeshTextureBindGroup<const I: usize>;
+impl<const I: usize> EntityRenderCommand for SetParticleMeshTextureBindGroup<I> {
+    type Param = (
+        SRes<ParticleImageBindGroups>,
+        SQuery<Read<ParticleMeshBatch>>,
+    );
+
+    fn render<'w>(
+        _view: Entity,
+        item: Entity,
+        (image_bind_groups, query_batch): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let batch = query_batch.get(item).unwrap();
+        let image_bind_groups = image_bind_groups.into_inner();
+
+        pass.set_bind_group(
+            I,
+            image_bind_groups
+                .values
+                .get(&Handle::weak(batch.image_handle_id))
+                .unwrap(),
+            &[],
+        );
+        RenderCommandResult::Success
+    }
+}
+
+pub struct SetParticleMeshBatchBindGroup<const I: usize>;
+impl<const I: usize> EntityRenderCommand for SetParticleMeshBatchBindGroup<I> {
+    type Param = (SRes<ParticleMeta>, SQuery<Read<ParticleMeshBatch>>);
+
+    fn render<'w>(
+        _view: Entity,
+        item: Entity,
+        (meta, query_batch): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let batch = query_batch.get(item).unwrap();
+        pass.set_bind_group(
+            I,
+            meta.into_inner().batch_bind_group.as_ref().unwrap(),
+            &[batch.uniform_offset],
+        );
+        RenderCommandResult::Success
+    }
+}
+
+pub struct DrawParticleMeshBatch;
+impl EntityRenderCommand for DrawParticleMeshBatch {
+    type Param = (
+        SRes<ParticleMeta>,
+        SRes<RenderAssets<Mesh>>,
+        SQuery<Read<ParticleMeshBatch>>,
+    );
+
+    fn render<'w>(
+        _view: Entity,
+        item: Entity,
+        (particle_meta, meshes, query_batch): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let batch = query_batch.get(item).unwrap();
+        let Some(gpu_mesh) = meshes.into_inner().get(&Handle::weak(batch.mesh_handle_id)) else {
+            return RenderCommandResult::Failure;
+        };
+        let particle_meta = particle_meta.into_inner();
+        pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(1, particle_meta.instances.buffer().unwrap().slice(..));
+        // We draw the mesh once for every instance contained in the batch range
+        match &gpu_mesh.buffer_info {
+            GpuBufferInfo::Indexed {
+                buffer,
+                index_format,
+                count,
+            } => {
+                pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(0..*count, 0, batch.range.clone());
+            }
+            GpuBufferInfo::NonIndexed { vertex_count } => {
+                pass.draw(0..*vertex_count, batch.range.clone());
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
@@ -1,9 +1,75 @@
 #![allow(clippy::needless_pass_by_value)]
-use crate::render::{ExtractedParticle, ExtractedParticles};
-use crate::{Particle, ParticleMaterial, ParticleRenderMode, ParticleSystem};
+use crate::components::TrailStripPoint;
+use crate::render::{
+    ExtractedCustomMaterials, ExtractedMaterial, ExtractedMeshParticle, ExtractedParticle,
+    ExtractedParticles, ExtractedTrail, ExtractedTrailPoint,
+};
+use crate::{
+    Particle, ParticleMaterial, ParticleRenderMode, ParticleSystem, ParticleTexture,
+    ParticleTextureSheet, ParticleTrails,
+};
+use bevy::asset::HandleId;
 use bevy::prelude::*;
 use bevy::render::RenderWorld;
 
+/// Retrieves the image of `material`, with its texture sheet and atlas if any
+fn material_texture<'a>(
+    material: &'a ParticleMaterial,
+    texture_atlases: &'a Assets<TextureAtlas>,
+) -> (
+    HandleId,
+    Option<(&'a ParticleTextureSheet, &'a TextureAtlas)>,
+) {
+    match &material.texture {
+        ParticleTexture::Image(image) => (image.id, None),
+        ParticleTexture::TextureSheet(sheet) => {
+            let atlas = texture_atlases
+                .get(sheet.texture_atlas.clone_weak())
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Failed to retrieve `TextureAtlas` from handle {:?}",
+                        sheet.texture_atlas.id
+                    )
+                });
+            (atlas.texture.id, Some((sheet, atlas)))
+        }
+    }
+}
+
+/// Faces the ribbon `points` towards the Camera, transforming them by `matrix` if any
+fn extract_trail(
+    points: &[TrailStripPoint],
+    matrix: Option<&Mat4>,
+    camera_translation: Vec3,
+    material: ExtractedMaterial,
+) -> ExtractedTrail {
+    let positions: Vec<Vec3> = points
+        .iter()
+        .map(|p| matrix.map_or(p.position, |m| m.transform_point3(p.position)))
+        .collect();
+    let mut side = Vec3::ZERO;
+    let points = points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let tangent =
+                positions[(i + 1).min(positions.len() - 1)] - positions[i.saturating_sub(1)];
+            // Keeps the previous side on degenerated segments
+            side = tangent
+                .cross(camera_translation - positions[i])
+                .try_normalize()
+                .unwrap_or(side);
+            ExtractedTrailPoint {
+                position: positions[i],
+                offset: side * point.width * 0.5,
+                color: point.color,
+                u: point.ratio,
+            }
+        })
+        .collect();
+    ExtractedTrail { material, points }
+}
+
 #[allow(clippy::type_complexity)]
 pub fn extract_particles(
     mut render_world: ResMut<RenderWorld>,
@@ -15,14 +81,25 @@ pub fn extract_particles(
         ),
     >,
     texture_atlases: Res<Assets<TextureAtlas>>,
+    time: Res<Time>,
+    mut last_camera_translation: Local<Option<Vec3>>,
     query: Query<(
+        Entity,
         &GlobalTransform,
         &ParticleSystem,
         &ParticleMaterial,
         &ParticleRenderMode,
         &ComputedVisibility,
+        Option<&ParticleTrails>,
     )>,
 ) {
+    // Custom materials are extracted first by their own plugins
+    let custom_materials = std::mem::take(
+        &mut render_world
+            .get_resource_mut::<ExtractedCustomMaterials>()
+            .unwrap()
+            .values,
+    );
     let mut extracted_particles = render_world
         .get_resource_mut::<ExtractedParticles>()
         .unwrap();
@@ -30,45 +107,92 @@ pub fn extract_particles(
     let camera_transform = cameras
         .get_single()
         .expect("Particle systems do not support multiple cameras yet");
+    let camera_velocity = match *last_camera_translation {
+        Some(last) if time.delta_seconds() > 0.0 => {
+            (camera_transform.translation - last) / time.delta_seconds()
+        }
+        _ => Vec3::ZERO,
+    };
+    *last_camera_translation = Some(camera_transform.translation);
     // Clear last frame extracted particles
     extracted_particles.particles.clear();
-    for (ps_transform, particles, material, render_mode, visibility) in query.iter() {
+    extracted_particles.mesh_particles.clear();
+    extracted_particles.trails.clear();
+    for (entity, ps_transform, particles, material, render_mode, visibility, trails) in query.iter()
+    {
         // skips invisible particle systems
         if !visibility.is_visible {
             continue;
         }
-        let (image_handle_id, anim) = match material {
-            ParticleMaterial::Image(image) => (image.id, None),
-            ParticleMaterial::TextureSheet(sheet) => {
-                let atlas = texture_atlases
-                    .get(sheet.texture_atlas.clone_weak())
-                    .unwrap_or_else(|| {
-                        panic!(
-                            "Failed to retrieve `TextureAtlas` from handle {:?}",
-                            sheet.texture_atlas.id
-                        )
-                    });
-                (atlas.texture.id, Some((sheet, atlas)))
-            }
-        };
+        let (image_handle_id, anim) = material_texture(material, &texture_atlases);
+        let material = ExtractedMaterial::new(
+            material,
+            image_handle_id,
+            custom_materials.get(&entity).copied(),
+        );
         let matrix: Mat4 = ps_transform.compute_matrix();
-        let extracted = particles.iter().cloned().map(|mut particle: Particle| {
+        if let Some(trails) = trails {
+            let ribbon_matrix = (!particles.world_space).then_some(&matrix);
+            let extracted = trails.strips(particles).into_iter().map(|strip| {
+                extract_trail(
+                    &strip,
+                    ribbon_matrix,
+                    camera_transform.translation,
+                    material,
+                )
+            });
+            extracted_particles.trails.extend(extracted);
+            if !trails.render_particles {
+                continue;
+            }
+        }
+        let transformed = particles.iter().map(|mut particle: Particle| {
             if !particles.world_space {
                 particle = particle.transformed(&matrix);
             }
-            let mut transform = Transform::from_translation(particle.translation);
-            render_mode.apply_to_particle(&particle, &mut transform, camera_transform);
-            ExtractedParticle {
-                image_handle_id,
-                transform,
+            let mut transform = Transform {
+                translation: particle.translation,
+                rotation: ps_transform.rotation,
+                ..Default::default()
+            };
+            render_mode.apply_to_particle(
+                &particle,
+                &mut transform,
+                camera_transform,
+                camera_velocity,
+                particles.world_space,
+            );
+            (particle, transform)
+        });
+        if let ParticleRenderMode::Mesh(mesh) = render_mode {
+            let extracted = transformed.map(|(particle, transform)| ExtractedMeshParticle {
+                mesh_handle_id: mesh.id,
+                material,
+                transform: transform.compute_matrix(),
                 color: particle.color,
-                rect: if let Some((sheet, atlas)) = anim {
-                    Some((sheet.mode.rect(atlas, &particle), atlas.size))
+                uv_rect: if let Some((sheet, atlas)) = anim {
+                    let rect = sheet.mode.rect(atlas, &particle);
+                    let (min, size) = (rect.min / atlas.size, rect.size() / atlas.size);
+                    Vec4::new(min.x, min.y, size.x, size.y)
                 } else {
-                    None
+                    Vec4::new(0.0, 0.0, 1.0, 1.0)
                 },
-                size: Vec2::splat(particle.size),
-            }
+                custom: [particle.custom_1, particle.custom_2],
+            });
+            extracted_particles.mesh_particles.extend(extracted);
+            continue;
+        }
+        let extracted = transformed.map(|(particle, transform)| ExtractedParticle {
+            material,
+            transform,
+            color: particle.color,
+            rect: if let Some((sheet, atlas)) = anim {
+                Some((sheet.mode.rect(atlas, &particle), atlas.size))
+            } else {
+                None
+            },
+            size: particle.size.truncate(),
+            custom: [particle.custom_1, particle.custom_2],
         });
         extracted_particles.particles.extend(extracted);
     }
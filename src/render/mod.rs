@@ -1,11 +1,14 @@
+use crate::{ParticleBlendMode, ParticleMaterial};
 use bevy::asset::HandleId;
-use bevy::prelude::{Color, Component, Handle, Image, Transform, Vec2};
-use bevy::render::render_resource::{BindGroup, BufferUsages, BufferVec};
+use bevy::prelude::{Color, Component, Entity, Handle, Image, Mat4, Transform, Vec2, Vec3, Vec4};
+use bevy::render::render_resource::std140::AsStd140;
+use bevy::render::render_resource::{BindGroup, BufferUsages, BufferVec, DynamicUniformVec};
 use bevy::sprite::Rect;
 use bevy::utils::HashMap;
 use bytemuck::{Pod, Zeroable};
 use std::ops::Range;
 
+pub mod custom_material;
 pub mod draw;
 pub mod extract;
 pub mod pipeline;
@@ -17,10 +20,31 @@ pub struct ParticleImageBindGroups {
     pub values: HashMap<Handle<Image>, BindGroup>,
 }
 
-#[derive(Component, Clone, Copy)]
-pub struct ExtractedParticle {
+/// Material of extracted particles, particles sharing the same material are batched together
+#[derive(Clone, Copy, PartialEq)]
+pub struct ExtractedMaterial {
     /// Texture handle id
     pub image_handle_id: HandleId,
+    /// Blend mode, part of the pipeline key
+    pub blend_mode: ParticleBlendMode,
+    /// Alpha cutoff of the [`ParticleBlendMode::Opaque`] mode
+    pub alpha_cutoff: f32,
+    /// Custom material handle id, if any
+    pub custom_material: Option<HandleId>,
+}
+
+/// Custom material handle ids of the particle systems, filled by the
+/// [`CustomParticleMaterialPlugin`](custom_material::CustomParticleMaterialPlugin) extraction
+/// systems
+#[derive(Default)]
+pub struct ExtractedCustomMaterials {
+    pub values: HashMap<Entity, HandleId>,
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct ExtractedParticle {
+    /// Particle material
+    pub material: ExtractedMaterial,
     /// World space transform
     pub transform: Transform,
     /// color tint
@@ -29,22 +53,94 @@ pub struct ExtractedParticle {
     pub rect: Option<(Rect, Vec2)>,
     /// Size of the sprite
     pub size: Vec2,
+    /// Particle custom data slots
+    pub custom: [Vec4; 2],
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct ExtractedMeshParticle {
+    /// Mesh handle id
+    pub mesh_handle_id: HandleId,
+    /// Particle material
+    pub material: ExtractedMaterial,
+    /// World space transform, scaled by the particle size
+    pub transform: Mat4,
+    /// color tint
+    pub color: Color,
+    /// Area of the texture, as normalized (`min`, `size`)
+    pub uv_rect: Vec4,
+    /// Particle custom data slots
+    pub custom: [Vec4; 2],
+}
+
+#[derive(Clone, Copy)]
+pub struct ExtractedTrailPoint {
+    /// World space position
+    pub position: Vec3,
+    /// World space offset of the ribbon edges, facing the camera
+    pub offset: Vec3,
+    /// color tint
+    pub color: Color,
+    /// Horizontal texture coordinate
+    pub u: f32,
+}
+
+#[derive(Clone)]
+pub struct ExtractedTrail {
+    /// Trail material
+    pub material: ExtractedMaterial,
+    /// Ribbon points, from the particle to the end of the trail
+    pub points: Vec<ExtractedTrailPoint>,
 }
 
 #[derive(Default)]
 pub struct ExtractedParticles {
     pub particles: Vec<ExtractedParticle>,
+    pub mesh_particles: Vec<ExtractedMeshParticle>,
+    pub trails: Vec<ExtractedTrail>,
 }
 
-/// Particle batch by texture handle
+/// Particle batch by material
 #[derive(Component, Clone)]
 pub struct ParticleBatch {
     /// Texture handle
     pub image_handle_id: HandleId,
-    /// Vertex buffer index range matching the texture id
+    /// Blend mode
+    pub blend_mode: ParticleBlendMode,
+    /// Custom material handle, the batch is queued by the matching
+    /// [`CustomParticleMaterialPlugin`](custom_material::CustomParticleMaterialPlugin) if any
+    pub custom_material: Option<HandleId>,
+    /// Offset of the batch [`ParticleBatchUniform`]
+    pub uniform_offset: u32,
+    /// Vertex buffer index range matching the material
     pub range: Range<u32>,
 }
 
+/// Instanced mesh particle batch by mesh and material
+#[derive(Component, Clone)]
+pub struct ParticleMeshBatch {
+    /// Mesh handle
+    pub mesh_handle_id: HandleId,
+    /// Texture handle
+    pub image_handle_id: HandleId,
+    /// Blend mode
+    pub blend_mode: ParticleBlendMode,
+    /// Custom material handle, the batch is queued by the matching
+    /// [`CustomParticleMaterialPlugin`](custom_material::CustomParticleMaterialPlugin) if any
+    pub custom_material: Option<HandleId>,
+    /// Offset of the batch [`ParticleBatchUniform`]
+    pub uniform_offset: u32,
+    /// Instance buffer index range matching the mesh and material
+    pub range: Range<u32>,
+}
+
+/// Per batch material parameters
+#[derive(Clone, Copy, AsStd140)]
+pub struct ParticleBatchUniform {
+    /// Alpha cutoff of the [`ParticleBlendMode::Opaque`] mode
+    pub alpha_cutoff: f32,
+}
+
 /// Single particle vertex representation
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
@@ -55,13 +151,36 @@ pub struct ParticleVertex {
     pub uv: [f32; 2],
     /// Vertex color
     pub color: u32,
+    /// First particle custom data slot
+    pub custom_1: [f32; 4],
+    /// Second particle custom data slot
+    pub custom_2: [f32; 4],
+}
+
+/// Single mesh particle instance representation
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ParticleInstance {
+    /// Particle model matrix columns
+    pub model: [[f32; 4]; 4],
+    /// Texture area, as normalized (`min`, `size`)
+    pub uv_rect: [f32; 4],
+    /// Particle color
+    pub color: u32,
+    /// First particle custom data slot
+    pub custom_1: [f32; 4],
+    /// Second particle custom data slot
+    pub custom_2: [f32; 4],
 }
 
 impl Default for ParticleMeta {
     fn default() -> Self {
         Self {
             vertices: BufferVec::new(BufferUsages::VERTEX),
+            instances: BufferVec::new(BufferUsages::VERTEX),
+            batch_uniforms: DynamicUniformVec::default(),
             view_bind_group: None,
+            batch_bind_group: None,
         }
     }
 }
@@ -69,6 +188,39 @@ impl Default for ParticleMeta {
 pub struct ParticleMeta {
     /// Every particle vertex information
     pub vertices: BufferVec<ParticleVertex>,
+    /// Every mesh particle instance information
+    pub instances: BufferVec<ParticleInstance>,
+    /// Every batch material parameters
+    pub batch_uniforms: DynamicUniformVec<ParticleBatchUniform>,
     /// Bind group corresponding to the pipeline `view_layout` bind group layout
     pub view_bind_group: Option<BindGroup>,
+    /// Bind group corresponding to the pipeline `batch_layout` bind group layout
+    pub batch_bind_group: Option<BindGroup>,
+}
+
+impl ExtractedMaterial {
+    /// Material of the particles using `material` with the image `image_handle_id` and the
+    /// optional `custom_material`
+    pub const fn new(
+        material: &ParticleMaterial,
+        image_handle_id: HandleId,
+        custom_material: Option<HandleId>,
+    ) -> Self {
+        Self {
+            image_handle_id,
+            blend_mode: material.blend_mode,
+            alpha_cutoff: material.alpha_cutoff,
+            custom_material,
+        }
+    }
+
+    /// Sorting and batching key
+    pub const fn key(&self) -> (HandleId, Option<HandleId>, u8, u32) {
+        (
+            self.image_handle_id,
+            self.custom_material,
+            self.blend_mode as u8,
+            self.alpha_cutoff.to_bits(),
+        )
+    }
 }
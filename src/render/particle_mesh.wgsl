@@ -0,0 +1,32 @@
+#import bevy_tickles::particle_types
+
+struct Vertex {
+    [[location(0)]] position: vec3<f32>;
+#ifdef VERTEX_UVS
+    [[location(1)]] uv: vec2<f32>;
+#endif
+    [[location(5)]] model_0: vec4<f32>;
+    [[location(6)]] model_1: vec4<f32>;
+    [[location(7)]] model_2: vec4<f32>;
+    [[location(8)]] model_3: vec4<f32>;
+    [[location(9)]] uv_rect: vec4<f32>;
+    [[location(10)]] color: u32;
+    [[location(11)]] custom_1: vec4<f32>;
+    [[location(12)]] custom_2: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let model = mat4x4<f32>(vertex.model_0, vertex.model_1, vertex.model_2, vertex.model_3);
+    var out: VertexOutput;
+#ifdef VERTEX_UVS
+    out.uv = vertex.uv_rect.xy + vertex.uv * vertex.uv_rect.zw;
+#else
+    out.uv = vertex.uv_rect.xy;
+#endif
+    out.position = view.view_proj * model * vec4<f32>(vertex.position, 1.0);
+    out.color = vec4<f32>((vec4<u32>(vertex.color) >> vec4<u32>(0u, 8u, 16u, 24u)) & vec4<u32>(255u)) / 255.0;
+    out.custom_1 = vertex.custom_1;
+    out.custom_2 = vertex.custom_2;
+    return out;
+}
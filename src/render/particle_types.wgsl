@@ -0,0 +1,27 @@
+#define_import_path bevy_tickles::particle_types
+
+struct View {
+    view_proj: mat4x4<f32>;
+    world_position: vec3<f32>;
+};
+[[group(0), binding(0)]]
+var<uniform> view: View;
+
+struct VertexOutput {
+    [[location(0)]] uv: vec2<f32>;
+    [[location(1)]] color: vec4<f32>;
+    [[location(2)]] custom_1: vec4<f32>;
+    [[location(3)]] custom_2: vec4<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[group(1), binding(0)]]
+var sprite_texture: texture_2d<f32>;
+[[group(1), binding(1)]]
+var sprite_sampler: sampler;
+
+struct ParticleBatch {
+    alpha_cutoff: f32;
+};
+[[group(2), binding(0)]]
+var<uniform> batch: ParticleBatch;
@@ -1,11 +1,15 @@
+use crate::render::ParticleBatchUniform;
+use crate::ParticleBlendMode;
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
+use bevy::render::mesh::MeshVertexBufferLayout;
 use bevy::render::render_resource::std140::AsStd140;
 use bevy::render::render_resource::{
-    BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, BlendState,
-    BufferBindingType, BufferSize, ColorTargetState, ColorWrites, CompareFunction, DepthBiasState,
-    DepthStencilState, FragmentState, FrontFace, MultisampleState, PolygonMode, PrimitiveState,
-    PrimitiveTopology, RenderPipelineDescriptor, SamplerBindingType, ShaderStages,
+    BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, BlendComponent,
+    BlendFactor, BlendOperation, BlendState, BufferBindingType, BufferSize, ColorTargetState,
+    ColorWrites, CompareFunction, DepthBiasState, DepthStencilState, FragmentState, FrontFace,
+    MultisampleState, PolygonMode, PrimitiveState, PrimitiveTopology, RenderPipelineDescriptor,
+    SamplerBindingType, ShaderStages, SpecializedMeshPipeline, SpecializedMeshPipelineError,
     SpecializedRenderPipeline, StencilFaceState, StencilState, TextureFormat, TextureSampleType,
     TextureViewDimension, VertexAttribute, VertexBufferLayout, VertexFormat, VertexState,
     VertexStepMode,
@@ -18,22 +22,90 @@ use bevy::render::view::ViewUniform;
 pub const PARTICLE_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3032357427511835453);
 
+#[allow(clippy::unreadable_literal)]
+pub const PARTICLE_MESH_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 7240813650943625117);
+
+/// Shared particle shader definitions and bindings, importable as `bevy_tickles::particle_types`
+#[allow(clippy::unreadable_literal)]
+pub const PARTICLE_TYPES_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 9120638516044780369);
+
+#[derive(Clone)]
+#[allow(clippy::struct_field_names)]
 pub struct ParticlePipeline {
     pub(crate) view_layout: BindGroupLayout,
     pub(crate) image_layout: BindGroupLayout,
+    pub(crate) batch_layout: BindGroupLayout,
+}
+
+/// Instanced mesh particles pipeline, sharing the [`ParticlePipeline`] bind group layouts and
+/// fragment shader
+#[derive(Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct ParticleMeshPipeline {
+    pub(crate) view_layout: BindGroupLayout,
+    pub(crate) image_layout: BindGroupLayout,
+    pub(crate) batch_layout: BindGroupLayout,
+}
+
+/// Color blending and depth writing of the particles rendered with `blend_mode`, with the matching
+/// fragment shader defs
+fn blend_settings(blend_mode: ParticleBlendMode) -> (Option<BlendState>, bool, Vec<String>) {
+    // Additive and multiply modes keep the destination alpha
+    let keep_alpha = BlendComponent {
+        src_factor: BlendFactor::Zero,
+        dst_factor: BlendFactor::One,
+        operation: BlendOperation::Add,
+    };
+    match blend_mode {
+        ParticleBlendMode::Alpha => (Some(BlendState::ALPHA_BLENDING), false, vec![]),
+        ParticleBlendMode::Premultiplied => (
+            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            false,
+            vec![String::from("BLEND_PREMULTIPLIED")],
+        ),
+        ParticleBlendMode::Additive => (
+            Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::SrcAlpha,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+                alpha: keep_alpha,
+            }),
+            false,
+            vec![],
+        ),
+        ParticleBlendMode::Multiply => (
+            Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Dst,
+                    dst_factor: BlendFactor::Zero,
+                    operation: BlendOperation::Add,
+                },
+                alpha: keep_alpha,
+            }),
+            false,
+            vec![String::from("BLEND_MULTIPLY")],
+        ),
+        ParticleBlendMode::Opaque => (None, true, vec![String::from("ALPHA_CUTOFF")]),
+    }
 }
 
 impl SpecializedRenderPipeline for ParticlePipeline {
-    type Key = ();
+    /// The particles blend mode
+    type Key = ParticleBlendMode;
 
-    fn specialize(&self, _key: Self::Key) -> RenderPipelineDescriptor {
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let (blend, depth_write_enabled, shader_defs) = blend_settings(key);
         RenderPipelineDescriptor {
             vertex: VertexState {
                 shader: PARTICLE_SHADER_HANDLE.typed::<Shader>(),
                 entry_point: "vertex".into(),
-                shader_defs: vec![],
+                shader_defs: shader_defs.clone(),
                 buffers: vec![VertexBufferLayout {
-                    array_stride: 24,
+                    array_stride: 56,
                     step_mode: VertexStepMode::Vertex,
                     attributes: vec![
                         // Vertex position
@@ -54,20 +126,35 @@ impl SpecializedRenderPipeline for ParticlePipeline {
                             offset: 20,
                             shader_location: 2,
                         },
+                        // Particle custom data
+                        VertexAttribute {
+                            format: VertexFormat::Float32x4,
+                            offset: 24,
+                            shader_location: 3,
+                        },
+                        VertexAttribute {
+                            format: VertexFormat::Float32x4,
+                            offset: 40,
+                            shader_location: 4,
+                        },
                     ],
                 }],
             },
             fragment: Some(FragmentState {
                 shader: PARTICLE_SHADER_HANDLE.typed::<Shader>(),
-                shader_defs: vec![],
+                shader_defs,
                 entry_point: "fragment".into(),
                 targets: vec![ColorTargetState {
                     format: TextureFormat::bevy_default(),
-                    blend: Some(BlendState::ALPHA_BLENDING),
+                    blend,
                     write_mask: ColorWrites::ALL,
                 }],
             }),
-            layout: Some(vec![self.view_layout.clone(), self.image_layout.clone()]),
+            layout: Some(vec![
+                self.view_layout.clone(),
+                self.image_layout.clone(),
+                self.batch_layout.clone(),
+            ]),
             primitive: PrimitiveState {
                 front_face: FrontFace::Ccw,
                 cull_mode: None,
@@ -79,7 +166,7 @@ impl SpecializedRenderPipeline for ParticlePipeline {
             },
             depth_stencil: Some(DepthStencilState {
                 format: TextureFormat::Depth32Float,
-                depth_write_enabled: false,
+                depth_write_enabled,
                 depth_compare: CompareFunction::Greater,
                 stencil: StencilState {
                     front: StencilFaceState::IGNORE,
@@ -142,9 +229,143 @@ impl FromWorld for ParticlePipeline {
             ],
             label: Some("particle_image_layout"),
         });
+
+        let batch_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: BufferSize::new(
+                        ParticleBatchUniform::std140_size_static() as u64
+                    ),
+                },
+                count: None,
+            }],
+            label: Some("particle_batch_layout"),
+        });
         Self {
             view_layout,
             image_layout,
+            batch_layout,
+        }
+    }
+}
+
+impl FromWorld for ParticleMeshPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let particle_pipeline = world.get_resource::<ParticlePipeline>().unwrap();
+        Self {
+            view_layout: particle_pipeline.view_layout.clone(),
+            image_layout: particle_pipeline.image_layout.clone(),
+            batch_layout: particle_pipeline.batch_layout.clone(),
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for ParticleMeshPipeline {
+    /// The mesh primitive topology and the particles blend mode
+    type Key = (PrimitiveTopology, ParticleBlendMode);
+
+    fn specialize(
+        &self,
+        (topology, blend_mode): Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let (blend, depth_write_enabled, mut shader_defs) = blend_settings(blend_mode);
+        let mut attributes = vec![Mesh::ATTRIBUTE_POSITION.at_shader_location(0)];
+        if layout.contains(Mesh::ATTRIBUTE_UV_0) {
+            attributes.push(Mesh::ATTRIBUTE_UV_0.at_shader_location(1));
+            shader_defs.push(String::from("VERTEX_UVS"));
         }
+        let mesh_buffer = layout.get_layout(&attributes)?;
+        // Particle instance attributes, matching `ParticleInstance`
+        let instance_formats = [
+            // Model matrix columns
+            VertexFormat::Float32x4,
+            VertexFormat::Float32x4,
+            VertexFormat::Float32x4,
+            VertexFormat::Float32x4,
+            // UV rect
+            VertexFormat::Float32x4,
+            // Color
+            VertexFormat::Uint32,
+            // Particle custom data
+            VertexFormat::Float32x4,
+            VertexFormat::Float32x4,
+        ];
+        let mut offset = 0;
+        let instance_attributes = instance_formats
+            .into_iter()
+            .zip(5..)
+            .map(|(format, shader_location)| {
+                let attribute = VertexAttribute {
+                    format,
+                    offset,
+                    shader_location,
+                };
+                offset += format.size();
+                attribute
+            })
+            .collect();
+        let instance_buffer = VertexBufferLayout {
+            array_stride: offset,
+            step_mode: VertexStepMode::Instance,
+            attributes: instance_attributes,
+        };
+        Ok(RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: PARTICLE_MESH_SHADER_HANDLE.typed::<Shader>(),
+                entry_point: "vertex".into(),
+                shader_defs: shader_defs.clone(),
+                buffers: vec![mesh_buffer, instance_buffer],
+            },
+            fragment: Some(FragmentState {
+                shader: PARTICLE_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![ColorTargetState {
+                    format: TextureFormat::bevy_default(),
+                    blend,
+                    write_mask: ColorWrites::ALL,
+                }],
+            }),
+            layout: Some(vec![
+                self.view_layout.clone(),
+                self.image_layout.clone(),
+                self.batch_layout.clone(),
+            ]),
+            primitive: PrimitiveState {
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: PolygonMode::Fill,
+                conservative: false,
+                topology,
+                strip_index_format: None,
+            },
+            depth_stencil: Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled,
+                depth_compare: CompareFunction::Greater,
+                stencil: StencilState {
+                    front: StencilFaceState::IGNORE,
+                    back: StencilFaceState::IGNORE,
+                    read_mask: 0,
+                    write_mask: 0,
+                },
+                bias: DepthBiasState {
+                    constant: 0,
+                    slope_scale: 0.0,
+                    clamp: 0.0,
+                },
+            }),
+            multisample: MultisampleState {
+                count: 4,
+                ..Default::default()
+            },
+            label: Some("particle_mesh_pipeline".into()),
+        })
     }
 }
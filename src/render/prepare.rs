@@ -1,4 +1,7 @@
-use crate::render::{ExtractedParticles, ParticleBatch, ParticleMeta, ParticleVertex};
+use crate::render::{
+    ExtractedMaterial, ExtractedMeshParticle, ExtractedParticles, ExtractedTrail, ParticleBatch,
+    ParticleBatchUniform, ParticleInstance, ParticleMeshBatch, ParticleMeta, ParticleVertex,
+};
 use bevy::math::const_vec2;
 use bevy::prelude::*;
 use bevy::render::renderer::{RenderDevice, RenderQueue};
@@ -19,6 +22,79 @@ const QUAD_UVS: [Vec2; 4] = [
     const_vec2!([0., 0.]),
 ];
 
+/// Encodes `color` as a single u32 to save space
+fn encode_color(color: Color) -> u32 {
+    let color = color.as_linear_rgba_f32();
+    (color[0] * 255.0) as u32
+        | ((color[1] * 255.0) as u32) << 8
+        | ((color[2] * 255.0) as u32) << 16
+        | ((color[3] * 255.0) as u32) << 24
+}
+
+/// Computes the vertices of the quads between every consecutive point of `trail`
+fn trail_vertices(trail: &ExtractedTrail) -> impl Iterator<Item = ParticleVertex> + '_ {
+    trail.points.iter().tuple_windows().flat_map(|(a, b)| {
+        let corners = [
+            (a.position - a.offset, [a.u, 1.0], a.color),
+            (b.position - b.offset, [b.u, 1.0], b.color),
+            (b.position + b.offset, [b.u, 0.0], b.color),
+            (a.position + a.offset, [a.u, 0.0], a.color),
+        ];
+        QUAD_INDICES.map(|i| {
+            let (position, uv, color) = corners[i];
+            ParticleVertex {
+                position: position.into(),
+                uv,
+                color: encode_color(color),
+                custom_1: [0.0; 4],
+                custom_2: [0.0; 4],
+            }
+        })
+    })
+}
+
+/// Adds the uniform of a batch of `material`, returning its dynamic offset
+fn push_batch_uniform(particle_meta: &mut ParticleMeta, material: &ExtractedMaterial) -> u32 {
+    particle_meta.batch_uniforms.push(ParticleBatchUniform {
+        alpha_cutoff: material.alpha_cutoff,
+    })
+}
+
+/// Groups the instanced mesh particles by mesh and material, and creates their batches
+fn prepare_mesh_particles(
+    commands: &mut Commands,
+    mesh_particles: &mut [ExtractedMeshParticle],
+    particle_meta: &mut ParticleMeta,
+) {
+    mesh_particles.sort_unstable_by_key(|p| (p.mesh_handle_id, p.material.key()));
+    particle_meta.instances.clear();
+    let mut index = 0;
+    for ((mesh_handle_id, material), group) in &mesh_particles
+        .iter()
+        .group_by(|p| (p.mesh_handle_id, p.material))
+    {
+        let start = index;
+        for particle in group {
+            particle_meta.instances.push(ParticleInstance {
+                model: particle.transform.to_cols_array_2d(),
+                uv_rect: particle.uv_rect.into(),
+                color: encode_color(particle.color),
+                custom_1: particle.custom[0].into(),
+                custom_2: particle.custom[1].into(),
+            });
+            index += 1;
+        }
+        commands.spawn_bundle((ParticleMeshBatch {
+            mesh_handle_id,
+            image_handle_id: material.image_handle_id,
+            blend_mode: material.blend_mode,
+            custom_material: material.custom_material,
+            uniform_offset: push_batch_uniform(particle_meta, &material),
+            range: (start..index),
+        },));
+    }
+}
+
 #[allow(clippy::needless_pass_by_value)]
 pub fn prepare_particles(
     mut commands: Commands,
@@ -36,15 +112,16 @@ pub fn prepare_particles(
             .z
             .partial_cmp(&b.transform.translation.z)
         {
-            Some(Ordering::Equal) | None => a.image_handle_id.cmp(&b.image_handle_id),
+            Some(Ordering::Equal) | None => a.material.key().cmp(&b.material.key()),
             Some(other) => other,
         }
     });
-    // Clear the vertex buffers
+    // Clear the vertex and uniform buffers
     particle_meta.vertices.clear();
+    particle_meta.batch_uniforms.clear();
     let mut index = 0;
-    // We group every consecutive particle with equal `image_handle_id` and create batches
-    for (image_handle_id, group) in &particles.iter().group_by(|p| p.image_handle_id) {
+    // We group every consecutive particle with equal `material` and create batches
+    for (material, group) in &particles.iter().group_by(|p| p.material) {
         // We compute the vertices for each group
         let vertices: Vec<ParticleVertex> = group
             .flat_map(|particle| {
@@ -56,12 +133,7 @@ pub fn prepare_particles(
                         *uv = (rect.min + *uv * rect_size) / size;
                     }
                 }
-                // encode color as a single u32 to save space
-                let color = particle.color.as_linear_rgba_f32();
-                let color = (color[0] * 255.0) as u32
-                    | ((color[1] * 255.0) as u32) << 8
-                    | ((color[2] * 255.0) as u32) << 16
-                    | ((color[3] * 255.0) as u32) << 24;
+                let color = encode_color(particle.color);
                 let matrix = particle.transform.compute_matrix();
                 let positions = QUAD_VERTEX_POSITIONS.map(|[x, y]| {
                     matrix
@@ -74,6 +146,8 @@ pub fn prepare_particles(
                         position: positions[*i],
                         uv: uvs[*i].into(),
                         color,
+                        custom_1: particle.custom[0].into(),
+                        custom_2: particle.custom[1].into(),
                     })
                     .collect::<Vec<ParticleVertex>>()
             })
@@ -84,12 +158,45 @@ pub fn prepare_particles(
             particle_meta.vertices.push(vertex);
         }
         commands.spawn_bundle((ParticleBatch {
-            image_handle_id,
+            image_handle_id: material.image_handle_id,
+            blend_mode: material.blend_mode,
+            custom_material: material.custom_material,
+            uniform_offset: push_batch_uniform(&mut particle_meta, &material),
             range: (index..(index + len)),
         },));
         index += len;
     }
+
+    // Trails are drawn as quads between consecutive ribbon points, grouped by material
+    let trails = &mut extracted_particles.trails;
+    trails.sort_unstable_by_key(|t| t.material.key());
+    for (material, group) in &trails.iter().group_by(|t| t.material) {
+        let start = index;
+        for vertex in group.flat_map(trail_vertices) {
+            particle_meta.vertices.push(vertex);
+            index += 1;
+        }
+        commands.spawn_bundle((ParticleBatch {
+            image_handle_id: material.image_handle_id,
+            blend_mode: material.blend_mode,
+            custom_material: material.custom_material,
+            uniform_offset: push_batch_uniform(&mut particle_meta, &material),
+            range: (start..index),
+        },));
+    }
     particle_meta
         .vertices
         .write_buffer(&render_device, &render_queue);
+
+    prepare_mesh_particles(
+        &mut commands,
+        &mut extracted_particles.mesh_particles,
+        &mut particle_meta,
+    );
+    particle_meta
+        .instances
+        .write_buffer(&render_device, &render_queue);
+    particle_meta
+        .batch_uniforms
+        .write_buffer(&render_device, &render_queue);
 }
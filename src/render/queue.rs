@@ -1,33 +1,132 @@
-use crate::render::draw::DrawParticle;
-use crate::render::pipeline::ParticlePipeline;
-use crate::render::{ParticleBatch, ParticleImageBindGroups, ParticleMeta};
-use bevy::core_pipeline::Transparent3d;
+use crate::render::draw::{DrawParticle, DrawParticleMesh};
+use crate::render::pipeline::{ParticleMeshPipeline, ParticlePipeline};
+use crate::render::{ParticleBatch, ParticleImageBindGroups, ParticleMeshBatch, ParticleMeta};
+use crate::ParticleBlendMode;
+use bevy::asset::HandleId;
+use bevy::core_pipeline::{AlphaMask3d, Transparent3d};
+use bevy::log;
 use bevy::prelude::*;
-use bevy::render::render_resource::BindingResource;
+use bevy::render::render_phase::DrawFunctionId;
+use bevy::render::render_resource::{BindGroupLayout, BindingResource, CachedRenderPipelineId};
 use bevy::render::{
     render_asset::RenderAssets,
     render_phase::{DrawFunctions, RenderPhase},
     render_resource::{
-        BindGroupDescriptor, BindGroupEntry, PipelineCache, SpecializedRenderPipelines,
+        BindGroupDescriptor, BindGroupEntry, PipelineCache, SpecializedMeshPipelines,
+        SpecializedRenderPipelines,
     },
     renderer::RenderDevice,
     view::ViewUniforms,
 };
 use bevy::sprite::SpriteAssetEvents;
 
+/// Creates the bind group of the image matching `image_handle_id` if it doesn't exist yet.
+///
+/// Returns `false` if the image is not loaded
+pub fn prepare_image_bind_group(
+    image_handle_id: HandleId,
+    image_bind_groups: &mut ParticleImageBindGroups,
+    gpu_images: &RenderAssets<Image>,
+    render_device: &RenderDevice,
+    layout: &BindGroupLayout,
+) -> bool {
+    let Some(gpu_image) = gpu_images.get(&Handle::weak(image_handle_id)) else {
+        return false;
+    };
+    image_bind_groups
+        .values
+        .entry(Handle::weak(image_handle_id))
+        .or_insert_with(|| {
+            render_device.create_bind_group(&BindGroupDescriptor {
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&gpu_image.texture_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&gpu_image.sampler),
+                    },
+                ],
+                label: Some("particle_image_bind_group"),
+                layout,
+            })
+        });
+    true
+}
+
+/// Queues the particle batch `entity` in the view render phases, using the `draw_functions`
+/// registered for the [`Transparent3d`] and [`AlphaMask3d`] phases.
+///
+/// Opaque batches are alpha tested and write the depth, so they are drawn in the
+/// [`AlphaMask3d`] phase, before every transparent item
+pub fn add_particle_batch(
+    (transparent_phase, alpha_mask_phase): (
+        &mut RenderPhase<Transparent3d>,
+        &mut RenderPhase<AlphaMask3d>,
+    ),
+    (transparent_draw_function, alpha_mask_draw_function): (DrawFunctionId, DrawFunctionId),
+    blend_mode: ParticleBlendMode,
+    pipeline: CachedRenderPipelineId,
+    entity: Entity,
+) {
+    if blend_mode == ParticleBlendMode::Opaque {
+        alpha_mask_phase.add(AlphaMask3d {
+            distance: 10.,
+            draw_function: alpha_mask_draw_function,
+            pipeline,
+            entity,
+        });
+    } else {
+        transparent_phase.add(Transparent3d {
+            distance: 10., // TODO: Try using `batch.range.min`
+            draw_function: transparent_draw_function,
+            pipeline,
+            entity,
+        });
+    }
+}
+
+/// Ids of the render command `C` draw functions in the [`Transparent3d`] and [`AlphaMask3d`]
+/// phases
+///
+/// # Panics
+///
+/// Panics if `C` is not registered in both phases
+#[must_use]
+pub fn draw_function_ids<C: 'static>(
+    transparent_draw_functions: &DrawFunctions<Transparent3d>,
+    alpha_mask_draw_functions: &DrawFunctions<AlphaMask3d>,
+) -> (DrawFunctionId, DrawFunctionId) {
+    (
+        transparent_draw_functions.read().get_id::<C>().unwrap(),
+        alpha_mask_draw_functions.read().get_id::<C>().unwrap(),
+    )
+}
+
 #[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
 pub fn queue_particles(
-    draw_functions: Res<DrawFunctions<Transparent3d>>,
+    (transparent_draw_functions, alpha_mask_draw_functions): (
+        Res<DrawFunctions<Transparent3d>>,
+        Res<DrawFunctions<AlphaMask3d>>,
+    ),
     render_device: Res<RenderDevice>,
     view_uniforms: Res<ViewUniforms>,
     particle_pipeline: Res<ParticlePipeline>,
+    particle_mesh_pipeline: Res<ParticleMeshPipeline>,
     mut pipelines: ResMut<SpecializedRenderPipelines<ParticlePipeline>>,
+    mut mesh_pipelines: ResMut<SpecializedMeshPipelines<ParticleMeshPipeline>>,
     mut pipeline_cache: ResMut<PipelineCache>,
     mut particle_meta: ResMut<ParticleMeta>,
     gpu_images: Res<RenderAssets<Image>>,
+    gpu_meshes: Res<RenderAssets<Mesh>>,
     batch_query: Query<(Entity, &ParticleBatch)>,
+    mesh_batch_query: Query<(Entity, &ParticleMeshBatch)>,
     mut image_bind_groups: ResMut<ParticleImageBindGroups>,
-    mut views: Query<&mut RenderPhase<Transparent3d>>,
+    mut views: Query<(
+        &mut RenderPhase<Transparent3d>,
+        &mut RenderPhase<AlphaMask3d>,
+    )>,
     events: Res<SpriteAssetEvents>,
 ) {
     // If an image has changed, the GpuImage has (probably) changed
@@ -40,6 +139,18 @@ pub fn queue_particles(
         };
     }
 
+    // Define the batch bind group
+    particle_meta.batch_bind_group = particle_meta.batch_uniforms.binding().map(|binding| {
+        render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: binding,
+            }],
+            label: Some("particle_batch_bind_group"),
+            layout: &particle_pipeline.batch_layout,
+        })
+    });
+
     if let Some(view_binding) = view_uniforms.uniforms.binding() {
         // Define the view bind group
         particle_meta.view_bind_group =
@@ -51,41 +162,74 @@ pub fn queue_particles(
                 label: Some("particle_view_bind_group"),
                 layout: &particle_pipeline.view_layout,
             }));
-        // Retrieve the particle drawing function
-        let draw_particle_function = draw_functions.read().get_id::<DrawParticle>().unwrap();
-        // Cache the specialized pipeline
-        let pipeline = pipelines.specialize(&mut pipeline_cache, &particle_pipeline, ());
+        // Retrieve the particle drawing functions
+        let draw_particle_function = draw_function_ids::<DrawParticle>(
+            &transparent_draw_functions,
+            &alpha_mask_draw_functions,
+        );
+        let draw_particle_mesh_function = draw_function_ids::<DrawParticleMesh>(
+            &transparent_draw_functions,
+            &alpha_mask_draw_functions,
+        );
 
-        for mut transparent_phase in views.iter_mut() {
-            for (entity, batch) in batch_query.iter() {
-                if let Some(gpu_image) = gpu_images.get(&Handle::weak(batch.image_handle_id)) {
-                    image_bind_groups
-                        .values
-                        .entry(Handle::weak(batch.image_handle_id))
-                        .or_insert_with(|| {
-                            render_device.create_bind_group(&BindGroupDescriptor {
-                                entries: &[
-                                    BindGroupEntry {
-                                        binding: 0,
-                                        resource: BindingResource::TextureView(
-                                            &gpu_image.texture_view,
-                                        ),
-                                    },
-                                    BindGroupEntry {
-                                        binding: 1,
-                                        resource: BindingResource::Sampler(&gpu_image.sampler),
-                                    },
-                                ],
-                                label: Some("particle_image_bind_group"),
-                                layout: &particle_pipeline.image_layout,
-                            })
-                        });
-                    transparent_phase.add(Transparent3d {
-                        distance: 10., // TODO: Try using `batch.range.min`
-                        draw_function: draw_particle_function,
+        for (mut transparent_phase, mut alpha_mask_phase) in views.iter_mut() {
+            // Custom material batches are queued by their own plugins
+            for (entity, batch) in batch_query
+                .iter()
+                .filter(|(_, b)| b.custom_material.is_none())
+            {
+                // Cache the specialized pipeline
+                let pipeline =
+                    pipelines.specialize(&mut pipeline_cache, &particle_pipeline, batch.blend_mode);
+                if prepare_image_bind_group(
+                    batch.image_handle_id,
+                    &mut image_bind_groups,
+                    &gpu_images,
+                    &render_device,
+                    &particle_pipeline.image_layout,
+                ) {
+                    add_particle_batch(
+                        (&mut transparent_phase, &mut alpha_mask_phase),
+                        draw_particle_function,
+                        batch.blend_mode,
+                        pipeline,
+                        entity,
+                    );
+                }
+            }
+            for (entity, batch) in mesh_batch_query
+                .iter()
+                .filter(|(_, b)| b.custom_material.is_none())
+            {
+                let Some(gpu_mesh) = gpu_meshes.get(&Handle::weak(batch.mesh_handle_id)) else {
+                    continue;
+                };
+                let pipeline = match mesh_pipelines.specialize(
+                    &mut pipeline_cache,
+                    &particle_mesh_pipeline,
+                    (gpu_mesh.primitive_topology, batch.blend_mode),
+                    &gpu_mesh.layout,
+                ) {
+                    Ok(pipeline) => pipeline,
+                    Err(err) => {
+                        log::error!("Failed to specialize the particle mesh pipeline: {}", err);
+                        continue;
+                    }
+                };
+                if prepare_image_bind_group(
+                    batch.image_handle_id,
+                    &mut image_bind_groups,
+                    &gpu_images,
+                    &render_device,
+                    &particle_mesh_pipeline.image_layout,
+                ) {
+                    add_particle_batch(
+                        (&mut transparent_phase, &mut alpha_mask_phase),
+                        draw_particle_mesh_function,
+                        batch.blend_mode,
                         pipeline,
                         entity,
-                    });
+                    );
                 }
             }
         }
@@ -0,0 +1,372 @@
+use crate::modifiers::{
+    ParticleModifier, ParticleModifiers, ParticleRngModifier, ParticleSystemModifier,
+};
+use crate::systems::{self, apply_modifier, apply_rng_modifier, apply_system_modifier};
+use crate::{ParticleSystemEmpty, ParticleSystemLabel};
+use bevy::app::App;
+use bevy::ecs::schedule::{ParallelSystemDescriptorCoercion, SystemStage};
+use bevy::ecs::world::Mut;
+use bevy::reflect::GetTypeRegistration;
+#[cfg(feature = "inspector")]
+use bevy_inspector_egui::RegisterInspectable;
+use std::any::type_name;
+
+/// Fixed time step used to simulate particle systems ahead in [`ParticleTimeStep::Variable`]
+/// mode, in seconds
+pub const SIMULATION_STEP: f32 = 1.0 / 60.0;
+
+/// Resource defining the time step of the particle simulation
+#[derive(Debug, Copy, Clone, Default)]
+pub enum ParticleTimeStep {
+    /// The particles are simulated once per frame, using the frame delta time
+    #[default]
+    Variable,
+    /// The particles are simulated in fixed steps: the frame delta time is accumulated and every
+    /// elapsed `step` is simulated, so a frame can run zero or multiple simulation steps.
+    ///
+    /// Combined with a seeded [`ParticleRng`](crate::ParticleRng), the simulation is
+    /// deterministic
+    Fixed {
+        /// The simulation step duration, in seconds
+        step: f32,
+        /// Maximum amount of steps simulated in a single frame, the remaining time is discarded
+        max_steps: u32,
+    },
+}
+
+/// Stage containing every particle simulation system.
+///
+/// The stage is ran by an exclusive system, allowing to run the simulation multiple times per frame.
+///
+/// Every system mutates the particle systems so they all run in a deterministic order: the
+/// particles are updated, emitted, then modified by each modifier in registration order, collided
+/// and finally trigger the sub emitters
+///
+/// Custom systems can be added to the `stage`, ordered with the
+/// [`ParticleSystemLabel`](crate::ParticleSystemLabel) labels
+pub struct ParticleSimulation {
+    /// The simulation systems
+    pub stage: SystemStage,
+    /// Accumulated time not yet simulated in [`ParticleTimeStep::Fixed`] mode
+    pub accumulator: f32,
+    /// Label of the last registered modifier system
+    last_modifier: Option<&'static str>,
+}
+
+/// Bound of the modifiers registered by [`AddParticleModifier`]: implemented by every
+/// [`Inspectable`](bevy_inspector_egui::Inspectable) type with the `inspector` feature, and by every
+/// type otherwise
+#[cfg(feature = "inspector")]
+pub trait InspectableModifier: bevy_inspector_egui::Inspectable {}
+
+#[cfg(feature = "inspector")]
+impl<T: bevy_inspector_egui::Inspectable> InspectableModifier for T {}
+
+/// Bound of the modifiers registered by [`AddParticleModifier`]: implemented by every
+/// `Inspectable` type with the `inspector` feature, and by every type otherwise
+#[cfg(not(feature = "inspector"))]
+pub trait InspectableModifier {}
+
+#[cfg(not(feature = "inspector"))]
+impl<T> InspectableModifier for T {}
+
+/// [`App`] extension registering custom particle modifiers.
+///
+/// The modifiers run in the [`ParticleSimulation`] stage with the
+/// [`ParticleSystemLabel::Modifier`] label, after the previously registered modifiers, and are
+/// registered for reflection and, with the `inspector` feature, in the inspector.
+///
+/// Note: Requires the [`ParticlesPlugin`](crate::ParticlesPlugin) to be added first
+pub trait AddParticleModifier {
+    /// Registers the [`ParticleModifier`] `M`
+    fn add_particle_modifier<M>(&mut self) -> &mut Self
+    where
+        M: ParticleModifier + GetTypeRegistration + InspectableModifier;
+
+    /// Registers the [`ParticleRngModifier`] `M`
+    fn add_particle_rng_modifier<M>(&mut self) -> &mut Self
+    where
+        M: ParticleRngModifier + GetTypeRegistration + InspectableModifier;
+
+    /// Registers the [`ParticleSystemModifier`] `M`
+    fn add_particle_system_modifier<M>(&mut self) -> &mut Self
+    where
+        M: ParticleSystemModifier + GetTypeRegistration + InspectableModifier;
+}
+
+impl ParticleTimeStep {
+    /// Fixed time step mode at `steps_per_second`, with at most 10 steps per frame
+    #[must_use]
+    pub const fn fixed(steps_per_second: f32) -> Self {
+        Self::Fixed {
+            step: steps_per_second.recip(),
+            max_steps: 10,
+        }
+    }
+
+    /// The step used to simulate particle systems ahead
+    pub(crate) const fn ahead_step(self) -> f32 {
+        match self {
+            Self::Variable => SIMULATION_STEP,
+            Self::Fixed { step, .. } => step,
+        }
+    }
+}
+
+impl Default for ParticleSimulation {
+    fn default() -> Self {
+        let mut stage = SystemStage::single_threaded();
+        stage
+            .add_system(systems::update_particle_system.label(ParticleSystemLabel::Update))
+            .add_system(
+                systems::emit_particles
+                    .label(ParticleSystemLabel::Emission)
+                    .after(ParticleSystemLabel::Update),
+            )
+            .add_system(
+                systems::apply_collisions
+                    .label(ParticleSystemLabel::Collision)
+                    .after(ParticleSystemLabel::Emission)
+                    .after(ParticleSystemLabel::Modifier),
+            )
+            .add_system(
+                systems::apply_sub_emitters
+                    .label(ParticleSystemLabel::SubEmitters)
+                    .after(ParticleSystemLabel::Collision),
+            )
+            .add_system(systems::record_particle_trails.after(ParticleSystemLabel::SubEmitters))
+            .add_system(
+                systems::send_empty_events
+                    .label(type_name::<ParticleSystemEmpty>())
+                    .after(ParticleSystemLabel::SubEmitters),
+            )
+            .add_system(systems::apply_stop_actions.after(type_name::<ParticleSystemEmpty>()))
+            .add_system(systems::compute_particles_aabb.after(ParticleSystemLabel::SubEmitters));
+        Self {
+            stage,
+            accumulator: 0.0,
+            last_modifier: None,
+        }
+    }
+}
+
+impl ParticleSimulation {
+    /// Adds a modifier system to the stage, running after the previously added modifiers
+    fn add_modifier_system<Params>(
+        &mut self,
+        system: impl ParallelSystemDescriptorCoercion<Params>,
+        label: &'static str,
+    ) -> &mut Self {
+        let mut system = system
+            .label(ParticleSystemLabel::Modifier)
+            .label(label)
+            .after(ParticleSystemLabel::Emission);
+        if let Some(last_modifier) = self.last_modifier {
+            system = system.after(last_modifier);
+        }
+        self.stage.add_system(system);
+        self.last_modifier = Some(label);
+        self
+    }
+
+    /// Registers the system applying the built-in modifier components and the
+    /// [`ParticleModifiers`] stack in a single pass over the particles.
+    ///
+    /// Note: Already registered by the [`ParticlesPlugin`](crate::ParticlesPlugin)
+    pub fn add_modifier_stack(&mut self) -> &mut Self {
+        self.add_modifier_system(systems::apply_modifiers, type_name::<ParticleModifiers>())
+    }
+
+    /// Registers the [`ParticleModifier`] `M`
+    pub fn add_modifier<M: ParticleModifier>(&mut self) -> &mut Self {
+        self.add_modifier_system(apply_modifier::<M>, type_name::<M>())
+    }
+
+    /// Registers the [`ParticleRngModifier`] `M`
+    pub fn add_rng_modifier<M: ParticleRngModifier>(&mut self) -> &mut Self {
+        self.add_modifier_system(apply_rng_modifier::<M>, type_name::<M>())
+    }
+
+    /// Registers the [`ParticleSystemModifier`] `M`
+    pub fn add_system_modifier<M: ParticleSystemModifier>(&mut self) -> &mut Self {
+        self.add_modifier_system(apply_system_modifier::<M>, type_name::<M>())
+    }
+
+    /// Computes the steps to simulate for a frame of `delta_time` seconds
+    ///
+    /// # Returns
+    ///
+    /// The step duration and the amount of steps
+    pub fn frame_steps(&mut self, delta_time: f32, time_step: ParticleTimeStep) -> (f32, u32) {
+        match time_step {
+            ParticleTimeStep::Variable => (delta_time, 1),
+            ParticleTimeStep::Fixed { step, max_steps } => {
+                if step <= 0.0 {
+                    return (0.0, 0);
+                }
+                self.accumulator += delta_time;
+                let steps = ((self.accumulator / step) as u32).min(max_steps);
+                self.accumulator -= step * steps as f32;
+                if steps == max_steps {
+                    // Discards the remaining time to avoid accumulating late steps
+                    self.accumulator = self.accumulator.min(step);
+                }
+                (step, steps)
+            }
+        }
+    }
+}
+
+impl AddParticleModifier for App {
+    fn add_particle_modifier<M>(&mut self) -> &mut Self
+    where
+        M: ParticleModifier + GetTypeRegistration + InspectableModifier,
+    {
+        self.register_type::<M>();
+        #[cfg(feature = "inspector")]
+        self.register_inspectable::<M>();
+        simulation(self).add_modifier::<M>();
+        self
+    }
+
+    fn add_particle_rng_modifier<M>(&mut self) -> &mut Self
+    where
+        M: ParticleRngModifier + GetTypeRegistration + InspectableModifier,
+    {
+        self.register_type::<M>();
+        #[cfg(feature = "inspector")]
+        self.register_inspectable::<M>();
+        simulation(self).add_rng_modifier::<M>();
+        self
+    }
+
+    fn add_particle_system_modifier<M>(&mut self) -> &mut Self
+    where
+        M: ParticleSystemModifier + GetTypeRegistration + InspectableModifier,
+    {
+        self.register_type::<M>();
+        #[cfg(feature = "inspector")]
+        self.register_inspectable::<M>();
+        simulation(self).add_system_modifier::<M>();
+        self
+    }
+}
+
+/// Retrieves the [`ParticleSimulation`] resource of `app`
+fn simulation(app: &mut App) -> Mut<'_, ParticleSimulation> {
+    app.world
+        .get_resource_mut::<ParticleSimulation>()
+        .expect("Could not retrieve `ParticleSimulation`, add the `ParticlesPlugin` first")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modifiers::{ParticleGravity, PerlinNoise};
+    use crate::{
+        BurstFired, EmitterFinished, ParticleDied, ParticleEmitter, ParticleParams, ParticleRng,
+        ParticleStorage, ParticleSystem, ParticleSystemEmpty, ParticleSystemStarted,
+    };
+    use bevy::ecs::event::Events;
+    use bevy::ecs::schedule::Stage;
+    use bevy::prelude::{GlobalTransform, Vec3, World};
+    use bevy::tasks::{ComputeTaskPool, TaskPool};
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    /// Simulates a particle system seeded with `seed` for `steps` fixed steps
+    fn simulate(seed: u64, steps: u32) -> ParticleStorage {
+        let mut world = World::new();
+        world.insert_resource(ComputeTaskPool(TaskPool::new()));
+        world.init_resource::<Events<ParticleSystemStarted>>();
+        world.init_resource::<Events<BurstFired>>();
+        world.init_resource::<Events<EmitterFinished>>();
+        world.init_resource::<Events<ParticleSystemEmpty>>();
+        world.init_resource::<Events<ParticleDied>>();
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                ParticleSystem::default(),
+                ParticleEmitter {
+                    rate: (50.0..=150.0).into(),
+                    ..Default::default()
+                },
+                ParticleParams {
+                    start_lifetime: (0.5..=1.5).into(),
+                    start_speed: (1.0..=3.0).into(),
+                    ..Default::default()
+                },
+                ParticleRng::from_seed(seed),
+                GlobalTransform::identity(),
+                ParticleGravity::default(),
+                PerlinNoise::default(),
+            ))
+            .id();
+        let mut simulation = ParticleSimulation::default();
+        simulation.add_modifier_stack();
+        for _ in 0..steps {
+            world
+                .get_mut::<ParticleSystem>(entity)
+                .unwrap()
+                .begin_step(STEP);
+            simulation.stage.run(&mut world);
+        }
+        world
+            .get::<ParticleSystem>(entity)
+            .unwrap()
+            .particles
+            .clone()
+    }
+
+    fn bits(values: &[Vec3]) -> Vec<[u32; 3]> {
+        values
+            .iter()
+            .map(|v| [v.x.to_bits(), v.y.to_bits(), v.z.to_bits()])
+            .collect()
+    }
+
+    #[test]
+    fn seeded_simulation_is_deterministic() {
+        let (a, b) = (simulate(42, 120), simulate(42, 120));
+        assert!(!a.is_empty());
+        assert_eq!(a.emission_indices(), b.emission_indices());
+        assert_eq!(bits(a.translations()), bits(b.translations()));
+        assert_eq!(bits(a.velocities()), bits(b.velocities()));
+        assert_eq!(bits(a.sizes()), bits(b.sizes()));
+        let lifetimes = |s: &ParticleStorage| -> Vec<u32> {
+            s.lifetimes().iter().map(|l| l.to_bits()).collect()
+        };
+        assert_eq!(lifetimes(&a), lifetimes(&b));
+        let other_seed = simulate(7, 120);
+        assert_ne!(bits(a.translations()), bits(other_seed.translations()));
+    }
+
+    #[test]
+    fn frame_steps_accumulate_and_cap() {
+        let mut simulation = ParticleSimulation::default();
+        let time_step = ParticleTimeStep::Fixed {
+            step: 0.1,
+            max_steps: 3,
+        };
+        // Less than a step: accumulated
+        assert_eq!(simulation.frame_steps(0.05, time_step), (0.1, 0));
+        assert!((simulation.accumulator - 0.05).abs() < 1e-6);
+        // The accumulated time completes a step
+        assert_eq!(simulation.frame_steps(0.06, time_step), (0.1, 1));
+        assert!((simulation.accumulator - 0.01).abs() < 1e-6);
+        // Capped at `max_steps`, the late time is discarded
+        assert_eq!(simulation.frame_steps(1.0, time_step), (0.1, 3));
+        assert!(simulation.accumulator <= 0.1);
+        // Variable steps don't accumulate
+        assert_eq!(
+            simulation.frame_steps(0.3, ParticleTimeStep::Variable),
+            (0.3, 1)
+        );
+        // Invalid steps simulate nothing
+        let invalid = ParticleTimeStep::Fixed {
+            step: 0.0,
+            max_steps: 3,
+        };
+        assert_eq!(simulation.frame_steps(1.0, invalid), (0.0, 0));
+    }
+}
@@ -1,77 +1,486 @@
 #![allow(clippy::needless_pass_by_value)]
-use crate::modifiers::{ParticleModifier, ParticleRngModifier, ParticleSystemModifier};
-use crate::{ParticleEmitter, ParticleParams, ParticleRng, ParticleSystem};
+use crate::events::{
+    BurstFired, EmitterFinished, ParticleDeathEvents, ParticleDied, ParticleSystemEmpty,
+    ParticleSystemStarted,
+};
+use crate::modifiers::{
+    BuiltinModifiers, MeshCollider, ParticleCollision, ParticleModifier, ParticleModifiers,
+    ParticleRngModifier, ParticleSystemModifier,
+};
+use crate::simulation::{ParticleSimulation, ParticleTimeStep};
+use crate::utilities::parallel::{self, PARTICLE_SYSTEM_BATCH_SIZE};
+use crate::{
+    ParticleEffect, ParticleEffectModifiers, ParticleEmitter, ParticleMaterial, ParticleParams,
+    ParticlePlayback, ParticleRenderMode, ParticleRng, ParticleSystem, ParticleTrails, StopAction,
+    SubEmitters,
+};
+use bevy::log;
 use bevy::prelude::*;
 use bevy::render::primitives::Aabb;
+use bevy::tasks::ComputeTaskPool;
 
-pub fn update_particle_system(mut query: Query<&mut ParticleSystem>, time: Res<Time>) {
-    let delta = time.delta_seconds();
-    for mut particle_system in query.iter_mut() {
-        particle_system.update(delta);
+pub fn spawn_particle_effects(
+    mut commands: Commands,
+    effects: Res<Assets<ParticleEffect>>,
+    query: Query<(Entity, &Handle<ParticleEffect>), Without<ParticleSystem>>,
+) {
+    for (entity, handle) in query.iter() {
+        if let Some(effect) = effects.get(handle) {
+            effect.insert(&mut commands.entity(entity));
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn reload_particle_effects(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<ParticleEffect>>,
+    effects: Res<Assets<ParticleEffect>>,
+    mut query: Query<(
+        Entity,
+        &Handle<ParticleEffect>,
+        &GlobalTransform,
+        &mut ParticleSystem,
+        &mut ParticleEmitter,
+        &mut ParticleParams,
+        &mut ParticleRenderMode,
+        &mut ParticleMaterial,
+        Option<&ParticleEffectModifiers>,
+    )>,
+) {
+    let modified_effects = events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } => Some(handle),
+            AssetEvent::Created { .. } | AssetEvent::Removed { .. } => None,
+        })
+        .filter_map(|handle| effects.get(handle).map(|effect| (handle, effect)));
+    for (modified_handle, effect) in modified_effects {
+        for (
+            entity,
+            _,
+            transform,
+            particle_system,
+            emitter,
+            params,
+            render_mode,
+            material,
+            inserted_modifiers,
+        ) in query
+            .iter_mut()
+            .filter(|(_, handle, ..)| *handle == modified_handle)
+        {
+            effect.reapply(
+                &mut commands.entity(entity),
+                inserted_modifiers,
+                transform,
+                particle_system,
+                emitter,
+                params,
+                render_mode,
+                material,
+            );
+        }
+    }
+}
+
+pub fn update_particle_system(
+    pool: Res<ComputeTaskPool>,
+    mut query: Query<(
+        Entity,
+        &mut ParticleSystem,
+        &GlobalTransform,
+        Option<&ParticleDeathEvents>,
+    )>,
+    mut death_events: EventWriter<ParticleDied>,
+) {
+    query.par_for_each_mut(
+        &pool,
+        PARTICLE_SYSTEM_BATCH_SIZE,
+        |(_, mut particle_system, ..)| {
+            if particle_system.is_simulated() {
+                let delta = particle_system.delta_time;
+                particle_system.update(delta, &pool);
+            }
+        },
+    );
+    for (entity, particle_system, transform, send_deaths) in query
+        .iter()
+        .filter(|(_, particle_system, ..)| particle_system.is_simulated())
+    {
+        if send_deaths.is_some() {
+            let matrix = if particle_system.world_space {
+                Mat4::IDENTITY
+            } else {
+                transform.compute_matrix()
+            };
+            death_events.send_batch(particle_system.dead_particles().iter().map(|particle| {
+                ParticleDied {
+                    entity,
+                    position: matrix.transform_point3(particle.translation),
+                    color: particle.color,
+                }
+            }));
+        }
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn emit_particles(
     mut query: Query<(
+        Entity,
         &mut ParticleSystem,
         &mut ParticleEmitter,
         &mut ParticleRng,
         &ParticleParams,
         &GlobalTransform,
+        Option<&ParticlePlayback>,
     )>,
-    time: Res<Time>,
+    mut started_events: EventWriter<ParticleSystemStarted>,
+    mut burst_events: EventWriter<BurstFired>,
+    mut finished_events: EventWriter<EmitterFinished>,
 ) {
-    let delta = time.delta_seconds();
-    for (mut particle_system, mut emitter, mut rng, params, transform) in query.iter_mut() {
+    for (entity, mut particle_system, mut emitter, mut rng, params, transform, playback) in
+        query.iter_mut()
+    {
+        if !particle_system.is_simulated() || playback.is_some_and(ParticlePlayback::is_stopped) {
+            continue;
+        }
+        let delta = particle_system.delta_time;
+        let emitter_time = emitter.cycle_ratio();
+        let (emitted, report) = emitter.emit_with_report(delta, rng.rng());
         particle_system.extend(
-            emitter
-                .emit(delta, rng.rng())
+            emitted
                 .into_iter()
-                .map(|e| params.get_particle(e.position, e.direction, rng.rng())),
+                .map(|e| params.get_particle(e.position, e.direction, emitter_time, rng.rng())),
             transform,
         );
+        if report.started {
+            started_events.send(ParticleSystemStarted { entity });
+        }
+        burst_events.send_batch(
+            report
+                .fired_bursts
+                .into_iter()
+                .map(|index| BurstFired { entity, index }),
+        );
+        if report.finished {
+            finished_events.send(EmitterFinished { entity });
+        }
     }
 }
 
-pub fn apply_system_modifier<M>(mut query: Query<(&mut ParticleSystem, &M)>, time: Res<Time>)
-where
+pub fn send_empty_events(
+    mut query: Query<(Entity, &mut ParticleSystem)>,
+    mut empty_events: EventWriter<ParticleSystemEmpty>,
+) {
+    for (entity, mut particle_system) in query.iter_mut() {
+        if particle_system.emptied {
+            particle_system.emptied = false;
+            empty_events.send(ParticleSystemEmpty { entity });
+        }
+    }
+}
+
+pub fn apply_stop_actions(
+    mut commands: Commands,
+    mut finished_events: EventReader<EmitterFinished>,
+    mut empty_events: EventReader<ParticleSystemEmpty>,
+    query: Query<(&StopAction, &ParticleEmitter, &ParticleSystem)>,
+) {
+    let mut entities: Vec<Entity> = finished_events
+        .iter()
+        .map(|e| e.entity)
+        .chain(empty_events.iter().map(|e| e.entity))
+        .collect();
+    entities.sort_unstable();
+    entities.dedup();
+    for entity in entities {
+        if let Ok((stop_action, emitter, particle_system)) = query.get(entity) {
+            if emitter.is_finished() && particle_system.is_empty() {
+                stop_action.apply(entity, &mut commands);
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn apply_sub_emitters(
+    mut query: Query<(
+        &mut ParticleSystem,
+        &mut ParticleEmitter,
+        &mut ParticleRng,
+        &ParticleParams,
+        &GlobalTransform,
+        Option<&SubEmitters>,
+    )>,
+) {
+    let mut emissions = vec![];
+    for (particle_system, _, _, _, transform, sub_emitters) in query.iter() {
+        for sub_emitter in sub_emitters.iter().flat_map(|s| s.0.iter()) {
+            let particles = sub_emitter.triggering_particles(particle_system, transform);
+            if !particles.is_empty() {
+                emissions.push((sub_emitter.clone(), particles));
+            }
+        }
+    }
+    for (sub_emitter, particles) in emissions {
+        match query.get_mut(sub_emitter.target) {
+            Ok((mut particle_system, mut emitter, mut rng, params, transform, _)) => {
+                sub_emitter.emit(
+                    &particles,
+                    (&mut particle_system, &mut emitter, params, transform),
+                    rng.rng(),
+                );
+            }
+            Err(e) => log::warn!(
+                "Failed to retrieve sub emitter target particle system {:?}: {}",
+                sub_emitter.target,
+                e
+            ),
+        }
+    }
+}
+
+pub fn record_particle_trails(
+    pool: Res<ComputeTaskPool>,
+    mut query: Query<(&mut ParticleSystem, &ParticleTrails)>,
+) {
+    query.par_for_each_mut(
+        &pool,
+        PARTICLE_SYSTEM_BATCH_SIZE,
+        |(mut particle_system, trails)| {
+            if particle_system.is_simulated() {
+                let delta = particle_system.delta_time;
+                trails.record(&mut particle_system.particles, delta);
+            }
+        },
+    );
+}
+
+pub fn update_mesh_colliders(
+    mut events: EventReader<AssetEvent<Mesh>>,
+    meshes: Res<Assets<Mesh>>,
+    mut query: Query<(&mut MeshCollider, &Handle<Mesh>, &GlobalTransform)>,
+) {
+    let modified_meshes: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } => Some(handle.id),
+            AssetEvent::Created { .. } | AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+    for (mut collider, handle, transform) in query.iter_mut() {
+        if modified_meshes.contains(&handle.id) {
+            collider.clear();
+        }
+        if !collider.is_outdated(handle.id, &transform.compute_matrix()) {
+            continue;
+        }
+        if let Some(mesh) = meshes.get(handle) {
+            collider.build(handle.id, mesh, transform);
+        }
+    }
+}
+
+pub fn apply_collisions(
+    pool: Res<ComputeTaskPool>,
+    mut query: Query<(&mut ParticleSystem, &ParticleCollision, &GlobalTransform)>,
+    mesh_colliders: Query<&MeshCollider>,
+) {
+    let mesh_colliders: Vec<&MeshCollider> = mesh_colliders.iter().collect();
+    query.par_for_each_mut(
+        &pool,
+        PARTICLE_SYSTEM_BATCH_SIZE,
+        |(mut particle_system, collision, transform)| {
+            if particle_system.is_simulated() {
+                let delta = particle_system.delta_time;
+                collision.apply(&mut particle_system, transform, &mesh_colliders, delta);
+            }
+        },
+    );
+}
+
+pub fn apply_system_modifier<M>(
+    pool: Res<ComputeTaskPool>,
+    mut query: Query<(&mut ParticleSystem, &M)>,
+) where
     M: ParticleSystemModifier,
 {
-    let delta = time.delta_seconds();
-    for (mut particle_system, modifier) in query.iter_mut() {
-        modifier.apply(&mut particle_system, delta);
-    }
+    query.par_for_each_mut(
+        &pool,
+        PARTICLE_SYSTEM_BATCH_SIZE,
+        |(mut particle_system, modifier)| {
+            if particle_system.is_simulated() {
+                let delta = particle_system.delta_time;
+                modifier.apply(&mut particle_system, delta);
+            }
+        },
+    );
 }
-pub fn apply_modifier<M>(mut query: Query<(&mut ParticleSystem, &M)>, time: Res<Time>)
+
+pub fn apply_modifier<M>(pool: Res<ComputeTaskPool>, mut query: Query<(&mut ParticleSystem, &M)>)
 where
     M: ParticleModifier,
 {
-    let delta = time.delta_seconds();
-    for (mut particle_system, modifier) in query.iter_mut() {
-        for particle in &mut particle_system.particles {
-            modifier.apply(particle, delta);
-        }
-    }
+    query.par_for_each_mut(
+        &pool,
+        PARTICLE_SYSTEM_BATCH_SIZE,
+        |(mut particle_system, modifier)| {
+            if particle_system.is_simulated() {
+                let delta = particle_system.delta_time;
+                parallel::for_each_chunk_mut(
+                    &pool,
+                    particle_system.particles.as_slice_mut(),
+                    |mut particles| {
+                        for mut particle in particles.iter_mut() {
+                            modifier.apply(&mut particle, delta);
+                        }
+                    },
+                );
+            }
+        },
+    );
 }
 
 pub fn apply_rng_modifier<M>(
+    pool: Res<ComputeTaskPool>,
     mut query: Query<(&mut ParticleSystem, &mut ParticleRng, &M)>,
-    time: Res<Time>,
 ) where
     M: ParticleRngModifier,
 {
-    let delta = time.delta_seconds();
-    for (mut particle_system, mut rng, modifier) in query.iter_mut() {
-        for particle in &mut particle_system.particles {
-            modifier.apply(rng.rng(), particle, delta);
-        }
-    }
+    query.par_for_each_mut(
+        &pool,
+        PARTICLE_SYSTEM_BATCH_SIZE,
+        |(mut particle_system, mut rng, modifier)| {
+            if particle_system.is_simulated() {
+                let delta = particle_system.delta_time;
+                parallel::for_each_rng_chunk_mut(
+                    &pool,
+                    particle_system.particles.as_slice_mut(),
+                    rng.rng(),
+                    |rng, mut particles| {
+                        for mut particle in particles.iter_mut() {
+                            modifier.apply(rng, &mut particle, delta);
+                        }
+                    },
+                );
+            }
+        },
+    );
 }
 
-pub fn compute_particles_aabb(mut query: Query<(&mut Aabb, &ParticleSystem)>) {
-    for (mut aabb, particles) in query.iter_mut() {
-        if let Some(bounding_box) = particles.compute_aabb() {
-            *aabb = bounding_box;
+pub fn apply_modifiers(
+    pool: Res<ComputeTaskPool>,
+    mut query: Query<(
+        &mut ParticleSystem,
+        &mut ParticleRng,
+        Option<&ParticleModifiers>,
+        BuiltinModifiers,
+    )>,
+) {
+    query.par_for_each_mut(
+        &pool,
+        PARTICLE_SYSTEM_BATCH_SIZE,
+        |(mut particle_system, mut rng, stack, builtins)| {
+            if !particle_system.is_simulated() || (stack.is_none() && builtins.is_empty()) {
+                return;
+            }
+            let delta = particle_system.delta_time;
+            parallel::for_each_rng_chunk_mut(
+                &pool,
+                particle_system.particles.as_slice_mut(),
+                rng.rng(),
+                |rng, mut particles| {
+                    for mut particle in particles.iter_mut() {
+                        builtins.apply(rng, &mut particle, delta);
+                        if let Some(stack) = stack {
+                            stack.apply_to_particle(rng, &mut particle, delta);
+                        }
+                    }
+                },
+            );
+        },
+    );
+}
+
+pub fn compute_particles_aabb(
+    pool: Res<ComputeTaskPool>,
+    mut query: Query<(&mut Aabb, &ParticleSystem)>,
+) {
+    query.par_for_each_mut(
+        &pool,
+        PARTICLE_SYSTEM_BATCH_SIZE,
+        |(mut aabb, particles)| {
+            if let Some(bounding_box) = particles.par_compute_aabb(&pool) {
+                *aabb = bounding_box;
+            }
+        },
+    );
+}
+
+pub fn run_particle_simulation(world: &mut World) {
+    let delta = world.resource::<Time>().delta_seconds();
+    let time_step = *world.resource::<ParticleTimeStep>();
+    world.resource_scope(|world, mut simulation: Mut<ParticleSimulation>| {
+        let mut query = world.query::<(
+            &mut ParticleSystem,
+            Option<&mut ParticleEmitter>,
+            Option<&mut ParticlePlayback>,
+        )>();
+        // Playback requests
+        for (mut particle_system, mut emitter, playback) in query.iter_mut(world) {
+            if let Some(mut playback) = playback {
+                if playback.clear_particles {
+                    playback.clear_particles = false;
+                    particle_system.clear();
+                }
+                if playback.reset_emitter {
+                    playback.reset_emitter = false;
+                    if let Some(emitter) = &mut emitter {
+                        emitter.reset();
+                    }
+                }
+                if playback.prewarm {
+                    playback.prewarm = false;
+                    if let Some(duration) = emitter.and_then(|e| e.prewarm_duration()) {
+                        // A full emission cycle, whatever the time scale
+                        playback.simulate_scaled(duration);
+                    }
+                }
+            }
         }
-    }
+        // Simulation ahead, in fixed steps
+        let ahead_step = time_step.ahead_step();
+        if ahead_step <= 0.0 {
+            for (_, _, playback) in query.iter_mut(world) {
+                if let Some(mut playback) = playback {
+                    playback.pending_simulation = 0.0;
+                }
+            }
+        }
+        while query
+            .iter(world)
+            .any(|(_, _, playback)| playback.is_some_and(|p| p.pending_simulation > 0.0))
+        {
+            for (mut particle_system, _, playback) in query.iter_mut(world) {
+                let step = playback.map_or(0.0, |mut playback| {
+                    let step = playback.pending_simulation.min(ahead_step);
+                    playback.pending_simulation -= step;
+                    step
+                });
+                particle_system.begin_step(step);
+            }
+            simulation.stage.run(world);
+        }
+        // Frame simulation
+        let (step, step_count) = simulation.frame_steps(delta, time_step);
+        for _ in 0..step_count {
+            for (mut particle_system, _, playback) in query.iter_mut(world) {
+                let delta = playback.map_or(step, |playback| playback.scaled_delta_time(step));
+                particle_system.begin_step(delta);
+            }
+            simulation.stage.run(world);
+        }
+    });
 }
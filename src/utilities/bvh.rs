@@ -0,0 +1,229 @@
+use bevy::prelude::Vec3;
+
+/// Maximum amount of triangles in a [`TriangleBvh`] leaf
+const MAX_LEAF_TRIANGLES: usize = 4;
+
+/// Tolerance used for ray/triangle intersections
+const EPSILON: f32 = 1e-6;
+
+/// Triangle defined by its three vertices
+#[derive(Debug, Copy, Clone)]
+pub struct Triangle(pub [Vec3; 3]);
+
+/// Axis aligned bounding box
+#[derive(Debug, Copy, Clone)]
+struct Bounds {
+    min: Vec3,
+    max: Vec3,
+}
+
+/// Node of a [`TriangleBvh`]
+///
+/// Leaves (`count > 0`) store the `count` triangles starting at `first`, other nodes have two
+/// children, stored at `first` and `first + 1`
+#[derive(Debug, Copy, Clone)]
+struct BvhNode {
+    bounds: Bounds,
+    first: usize,
+    count: usize,
+}
+
+/// Bounding volume hierarchy of triangles, for fast spatial queries
+#[derive(Debug, Clone)]
+pub struct TriangleBvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<Triangle>,
+}
+
+impl Triangle {
+    fn bounds(&self) -> Bounds {
+        let [a, b, c] = self.0;
+        Bounds {
+            min: a.min(b).min(c),
+            max: a.max(b).max(c),
+        }
+    }
+
+    fn centroid(&self) -> Vec3 {
+        let [a, b, c] = self.0;
+        (a + b + c) / 3.0
+    }
+
+    /// The triangle unit normal, following the counter clockwise winding order
+    pub fn normal(&self) -> Option<Vec3> {
+        let [a, b, c] = self.0;
+        (b - a).cross(c - a).try_normalize()
+    }
+
+    /// Computes the intersection of the segment from `start` to `end` with the triangle
+    /// (Möller–Trumbore algorithm).
+    ///
+    /// # Returns
+    ///
+    /// The intersection ratio along the segment, in `0..=1`
+    #[allow(clippy::many_single_char_names)]
+    pub fn intersect_segment(&self, start: Vec3, end: Vec3) -> Option<f32> {
+        let [a, b, c] = self.0;
+        let direction = end - start;
+        let edge_1 = b - a;
+        let edge_2 = c - a;
+        let p = direction.cross(edge_2);
+        let determinant = edge_1.dot(p);
+        if determinant.abs() < EPSILON {
+            return None;
+        }
+        let inverse = determinant.recip();
+        let s = start - a;
+        let u = s.dot(p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(edge_1);
+        let v = direction.dot(q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = edge_2.dot(q) * inverse;
+        (0.0..=1.0).contains(&t).then_some(t)
+    }
+
+    /// Computes the point of the triangle closest to `point` (from *Real-Time Collision
+    /// Detection*, Christer Ericson)
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        let [a, b, c] = self.0;
+        let ab = b - a;
+        let ac = c - a;
+        let ap = point - a;
+        let d1 = ab.dot(ap);
+        let d2 = ac.dot(ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return a;
+        }
+        let bp = point - b;
+        let d3 = ab.dot(bp);
+        let d4 = ac.dot(bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return b;
+        }
+        let vc = d1.mul_add(d4, -(d3 * d2));
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+        let cp = point - c;
+        let d5 = ab.dot(cp);
+        let d6 = ac.dot(cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return c;
+        }
+        let vb = d5.mul_add(d2, -(d1 * d6));
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+        let va = d3.mul_add(d6, -(d5 * d4));
+        if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+        let denominator = (va + vb + vc).recip();
+        a + ab * (vb * denominator) + ac * (vc * denominator)
+    }
+}
+
+impl Bounds {
+    fn empty() -> Self {
+        Self {
+            min: Vec3::splat(f32::MAX),
+            max: Vec3::splat(f32::MIN),
+        }
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+}
+
+impl TriangleBvh {
+    /// Builds the hierarchy from `triangles`
+    pub fn new(triangles: Vec<Triangle>) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::with_capacity((triangles.len() / MAX_LEAF_TRIANGLES + 1) * 2),
+            triangles,
+        };
+        bvh.nodes.push(BvhNode {
+            bounds: Bounds::empty(),
+            first: 0,
+            count: 0,
+        });
+        bvh.build(0, 0, bvh.triangles.len());
+        bvh
+    }
+
+    fn build(&mut self, node_index: usize, start: usize, end: usize) {
+        let triangles = &mut self.triangles[start..end];
+        let bounds = triangles
+            .iter()
+            .fold(Bounds::empty(), |bounds, t| bounds.union(t.bounds()));
+        self.nodes[node_index].bounds = bounds;
+        if triangles.len() <= MAX_LEAF_TRIANGLES {
+            self.nodes[node_index].first = start;
+            self.nodes[node_index].count = triangles.len();
+            return;
+        }
+        // We split along the longest axis of the centroids bounds
+        let centroid_bounds = triangles.iter().fold(Bounds::empty(), |bounds, t| {
+            let centroid = t.centroid();
+            bounds.union(Bounds {
+                min: centroid,
+                max: centroid,
+            })
+        });
+        let extents = centroid_bounds.max - centroid_bounds.min;
+        let axis = if extents.x >= extents.y && extents.x >= extents.z {
+            0
+        } else if extents.y >= extents.z {
+            1
+        } else {
+            2
+        };
+        let middle = triangles.len() / 2;
+        triangles.select_nth_unstable_by(middle, |a, b| {
+            a.centroid()[axis].total_cmp(&b.centroid()[axis])
+        });
+        let left = self.nodes.len();
+        self.nodes.extend([self.nodes[node_index]; 2]);
+        self.nodes[node_index].first = left;
+        self.nodes[node_index].count = 0;
+        self.build(left, start, start + middle);
+        self.build(left + 1, start + middle, end);
+    }
+
+    /// Calls `f` for every triangle whose bounds intersect the box defined by `min` and `max`
+    pub fn query(&self, min: Vec3, max: Vec3, mut f: impl FnMut(&Triangle)) {
+        if self.triangles.is_empty() {
+            return;
+        }
+        let bounds = Bounds { min, max };
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bounds.intersects(&bounds) {
+                continue;
+            }
+            if node.count > 0 {
+                self.triangles[node.first..node.first + node.count]
+                    .iter()
+                    .filter(|t| t.bounds().intersects(&bounds))
+                    .for_each(&mut f);
+            } else {
+                stack.push(node.first);
+                stack.push(node.first + 1);
+            }
+        }
+    }
+}
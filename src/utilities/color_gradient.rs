@@ -1,7 +1,8 @@
 use bevy::prelude::{Color, Reflect, Vec4};
 use bevy::reflect::FromReflect;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Copy, Clone, Reflect)]
+#[derive(Debug, Default, Copy, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 struct GradientPoint {
     pub pos: f32,
@@ -9,7 +10,7 @@ struct GradientPoint {
 }
 
 /// Color gradient
-#[derive(Clone, Debug, Reflect)]
+#[derive(Clone, Debug, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub struct ColorGradient {
     points: Vec<GradientPoint>,
@@ -1,8 +1,9 @@
 use crate::utilities::ColorGradient;
 use bevy::prelude::{Color, Reflect};
+use serde::{Deserialize, Serialize};
 
 /// Either a fixed color or a color gradient
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
 pub enum ColorOrGradient {
     /// Single color
@@ -0,0 +1,326 @@
+use bevy::prelude::Reflect;
+use bevy::reflect::FromReflect;
+use serde::{Deserialize, Serialize};
+
+/// Interpolation between a [`Keyframe`] and the next one
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub enum CurveInterpolation {
+    /// Linear interpolation
+    #[default]
+    Linear,
+    /// The value is kept until the next keyframe
+    Step,
+    /// Cubic Hermite interpolation, using the keyframes tangents
+    Hermite,
+}
+
+/// Keyframe of a [`Curve`]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+pub struct Keyframe {
+    /// Keyframe position on the curve
+    pub time: f32,
+    /// Keyframe value
+    pub value: f32,
+    /// Incoming tangent, used if the previous keyframe has a [`CurveInterpolation::Hermite`]
+    /// interpolation
+    #[serde(default)]
+    pub in_tangent: f32,
+    /// Outgoing tangent, used if the keyframe has a [`CurveInterpolation::Hermite`]
+    /// interpolation
+    #[serde(default)]
+    pub out_tangent: f32,
+    /// Interpolation towards the next keyframe
+    #[serde(default)]
+    pub interpolation: CurveInterpolation,
+}
+
+/// Animation curve, made of keyframes.
+///
+/// Serialized as its list of keyframes, which are sorted by time when deserialized
+#[derive(Clone, Debug, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
+#[serde(from = "Vec<Keyframe>", into = "Vec<Keyframe>")]
+pub struct Curve {
+    keyframes: Vec<Keyframe>,
+}
+
+impl FromReflect for Keyframe {
+    fn from_reflect(reflect: &dyn Reflect) -> Option<Self> {
+        reflect.any().downcast_ref::<Self>().copied()
+    }
+}
+
+impl Keyframe {
+    /// Keyframe with a [`CurveInterpolation::Linear`] interpolation
+    #[must_use]
+    pub const fn linear(time: f32, value: f32) -> Self {
+        Self {
+            time,
+            value,
+            in_tangent: 0.0,
+            out_tangent: 0.0,
+            interpolation: CurveInterpolation::Linear,
+        }
+    }
+
+    /// Keyframe with a [`CurveInterpolation::Step`] interpolation
+    #[must_use]
+    pub const fn step(time: f32, value: f32) -> Self {
+        Self {
+            interpolation: CurveInterpolation::Step,
+            ..Self::linear(time, value)
+        }
+    }
+
+    /// Keyframe with a [`CurveInterpolation::Hermite`] interpolation
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The keyframe position on the curve
+    /// * `value` - The keyframe value
+    /// * `in_tangent` - The incoming tangent
+    /// * `out_tangent` - The outgoing tangent
+    #[must_use]
+    pub const fn hermite(time: f32, value: f32, in_tangent: f32, out_tangent: f32) -> Self {
+        Self {
+            time,
+            value,
+            in_tangent,
+            out_tangent,
+            interpolation: CurveInterpolation::Hermite,
+        }
+    }
+}
+
+impl From<f32> for Curve {
+    fn from(value: f32) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl From<(f32, f32)> for Curve {
+    fn from((start, end): (f32, f32)) -> Self {
+        Self::linear(start, end)
+    }
+}
+
+impl From<Vec<Keyframe>> for Curve {
+    /// Sorts the `keyframes` by time. Keyframes with a non finite time are discarded, and only
+    /// the last keyframe is kept for a given time
+    fn from(keyframes: Vec<Keyframe>) -> Self {
+        keyframes
+            .into_iter()
+            .filter(|k| k.time.is_finite())
+            .fold(Self::empty(), Self::add_keyframe)
+    }
+}
+
+impl From<Curve> for Vec<Keyframe> {
+    fn from(curve: Curve) -> Self {
+        curve.keyframes
+    }
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Self::constant(1.0)
+    }
+}
+
+impl Curve {
+    /// Initializes an empty curve
+    #[must_use]
+    #[inline]
+    pub const fn empty() -> Self {
+        Self { keyframes: vec![] }
+    }
+
+    /// Curve with a constant `value`
+    #[must_use]
+    pub fn constant(value: f32) -> Self {
+        Self::empty().add_keyframe(Keyframe::linear(0.0, value))
+    }
+
+    /// Linear curve from `start` at `0` to `end` at `1`
+    #[must_use]
+    pub fn linear(start: f32, end: f32) -> Self {
+        Self::empty()
+            .add_keyframe(Keyframe::linear(0.0, start))
+            .add_keyframe(Keyframe::linear(1.0, end))
+    }
+
+    /// Smooth curve from `start` at `0` to `end` at `1`, with flat tangents
+    #[must_use]
+    pub fn ease_in_out(start: f32, end: f32) -> Self {
+        Self::empty()
+            .add_keyframe(Keyframe::hermite(0.0, start, 0.0, 0.0))
+            .add_keyframe(Keyframe::hermite(1.0, end, 0.0, 0.0))
+    }
+
+    /// The curve keyframes, sorted by time
+    #[must_use]
+    #[inline]
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Adds a linear keyframe at `time` with `value`.
+    ///
+    /// Note: If a keyframe at `time` already exists, it will be overwritten
+    #[must_use]
+    pub fn add_point(self, time: f32, value: f32) -> Self {
+        self.add_keyframe(Keyframe::linear(time, value))
+    }
+
+    /// Adds a `keyframe` to the curve.
+    ///
+    /// Note: If a keyframe at the same time already exists, it will be overwritten
+    #[must_use]
+    pub fn add_keyframe(mut self, keyframe: Keyframe) -> Self {
+        if let Some(index) = self
+            .keyframes
+            .iter()
+            .position(|k| (k.time - keyframe.time).abs() < f32::EPSILON)
+        {
+            self.keyframes[index] = keyframe;
+        } else {
+            match self.keyframes.iter().position(|k| k.time >= keyframe.time) {
+                None => self.keyframes.push(keyframe),
+                Some(index) => self.keyframes.insert(index, keyframe),
+            }
+        }
+        self
+    }
+
+    /// Evaluates the curve at `time`. Outside of the keyframes range, the first or last keyframe
+    /// value is returned. An empty curve evaluates to `0`, and a `NaN` time to the first keyframe
+    /// value
+    #[must_use]
+    pub fn evaluate(&self, time: f32) -> f32 {
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return 0.0;
+        };
+        if self.keyframes.len() == 1 || time.is_nan() || time <= first.time {
+            return first.value;
+        }
+        if time >= last.time {
+            return last.value;
+        }
+        // The first keyframe after `time`, which can't be the first one
+        let index = self
+            .keyframes
+            .iter()
+            .position(|k| k.time > time)
+            .unwrap_or(self.keyframes.len() - 1)
+            .max(1);
+        let (from, to) = (&self.keyframes[index - 1], &self.keyframes[index]);
+        let duration = to.time - from.time;
+        let t = (time - from.time) / duration;
+        match from.interpolation {
+            CurveInterpolation::Linear => (to.value - from.value).mul_add(t, from.value),
+            CurveInterpolation::Step => from.value,
+            CurveInterpolation::Hermite => {
+                let t2 = t * t;
+                let t3 = t2 * t;
+                let h00 = 2.0f32.mul_add(t3, -3.0 * t2) + 1.0;
+                let h10 = 2.0f32.mul_add(-t2, t3) + t;
+                let h01 = (-2.0f32).mul_add(t3, 3.0 * t2);
+                let h11 = t3 - t2;
+                let tangents = h10.mul_add(from.out_tangent, h11 * to.in_tangent) * duration;
+                h00.mul_add(from.value, h01.mul_add(to.value, tangents))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!(
+            (value - expected).abs() < 1e-5,
+            "{value} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn linear_segments() {
+        let curve = Curve::linear(2.0, 4.0).add_point(2.0, 0.0);
+        assert_close(curve.evaluate(0.25), 2.5);
+        assert_close(curve.evaluate(1.0), 4.0);
+        assert_close(curve.evaluate(1.5), 2.0);
+    }
+
+    #[test]
+    fn step_segments() {
+        let curve = Curve::empty()
+            .add_keyframe(Keyframe::step(0.0, 1.0))
+            .add_keyframe(Keyframe::step(1.0, 3.0))
+            .add_point(2.0, 5.0);
+        assert_close(curve.evaluate(0.5), 1.0);
+        assert_close(curve.evaluate(0.999), 1.0);
+        assert_close(curve.evaluate(1.0), 3.0);
+        assert_close(curve.evaluate(1.5), 3.0);
+    }
+
+    #[test]
+    fn hermite_segments() {
+        // Flat tangents ease in and out
+        let curve = Curve::ease_in_out(0.0, 1.0);
+        assert_close(curve.evaluate(0.25), 0.156_25);
+        assert_close(curve.evaluate(0.5), 0.5);
+        assert_close(curve.evaluate(0.75), 0.843_75);
+        // Tangents matching the slope give a straight line, on a segment of any duration
+        let curve = Curve::empty()
+            .add_keyframe(Keyframe::hermite(0.0, 0.0, 0.0, 0.5))
+            .add_keyframe(Keyframe::hermite(2.0, 1.0, 0.5, 0.0));
+        assert_close(curve.evaluate(0.5), 0.25);
+        assert_close(curve.evaluate(1.5), 0.75);
+    }
+
+    #[test]
+    fn evaluation_outside_of_the_keyframes() {
+        let curve = Curve::empty().add_point(0.5, 2.0).add_point(1.0, 4.0);
+        assert_close(curve.evaluate(0.0), 2.0);
+        assert_close(curve.evaluate(-10.0), 2.0);
+        assert_close(curve.evaluate(1.5), 4.0);
+        assert_close(curve.evaluate(f32::INFINITY), 4.0);
+        assert_close(curve.evaluate(f32::NAN), 2.0);
+    }
+
+    #[test]
+    fn single_keyframe_and_empty_curves() {
+        let curve = Curve::constant(3.0);
+        assert_close(curve.evaluate(-1.0), 3.0);
+        assert_close(curve.evaluate(0.5), 3.0);
+        assert_close(curve.evaluate(2.0), 3.0);
+        let curve = Curve::empty();
+        assert_close(curve.evaluate(0.0), 0.0);
+        assert_close(curve.evaluate(1.0), 0.0);
+    }
+
+    #[test]
+    fn deserialized_keyframes_are_sorted_and_deduplicated() {
+        let curve: Curve = ron::from_str(
+            "[
+                (time: 1.0, value: 2.0),
+                (time: 0.0, value: 1.0, interpolation: Step),
+                (time: 0.5, value: 5.0),
+                (time: 1.0, value: 3.0),
+                (time: inf, value: 4.0),
+            ]",
+        )
+        .unwrap();
+        let times: Vec<_> = curve.keyframes().iter().map(|k| k.time).collect();
+        let values: Vec<_> = curve.keyframes().iter().map(|k| k.value).collect();
+        assert_eq!(times, [0.0, 0.5, 1.0]);
+        assert_eq!(values, [1.0, 5.0, 3.0]);
+        assert_eq!(curve.keyframes()[0].interpolation, CurveInterpolation::Step);
+        let serialized = ron::to_string(&curve).unwrap();
+        let deserialized: Curve = ron::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.keyframes(), curve.keyframes());
+    }
+}
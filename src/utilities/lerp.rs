@@ -0,0 +1,164 @@
+use crate::ColorGradient;
+use bevy::prelude::{Color, Quat, Reflect, Vec2, Vec3, Vec4};
+use rand::Rng;
+use std::fmt::Debug;
+
+/// Value which can be interpolated and randomized, allowing its use in a
+/// [`RangeOrFixed`](crate::RangeOrFixed)
+pub trait Lerp: Copy + Debug + Send + Sync + Reflect + Default {
+    /// Interpolates between `self` and `other`, `t` being between `0` and `1`
+    #[must_use]
+    fn lerp(self, other: Self, t: f32) -> Self;
+
+    /// Multiplies the value by `factor`
+    #[must_use]
+    fn scale(self, factor: f32) -> Self;
+
+    /// Picks a random value between `min` and `max` using `rng`
+    #[must_use]
+    fn random_between(min: Self, max: Self, rng: &mut impl Rng) -> Self {
+        min.lerp(max, rng.gen_range(0.0..=1.0))
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        (other - self).mul_add(t, self)
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        self * factor
+    }
+
+    fn random_between(min: Self, max: Self, rng: &mut impl Rng) -> Self {
+        rng.gen_range(min..=max)
+    }
+}
+
+/// Integers are interpolated and scaled as `f32`, rounding the result to the nearest integer
+macro_rules! impl_integer_lerp {
+    ($($t:ty),*) => {
+        $(
+            impl Lerp for $t {
+                fn lerp(self, other: Self, t: f32) -> Self {
+                    (self as f32).lerp(other as f32, t).round() as Self
+                }
+
+                fn scale(self, factor: f32) -> Self {
+                    (self as f32 * factor).round() as Self
+                }
+
+                fn random_between(min: Self, max: Self, rng: &mut impl Rng) -> Self {
+                    rng.gen_range(min..=max)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_lerp!(usize, u32, i32);
+
+impl Lerp for Vec2 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self::lerp(self, other, t)
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        self * factor
+    }
+
+    fn random_between(min: Self, max: Self, rng: &mut impl Rng) -> Self {
+        Self::new(
+            f32::random_between(min.x, max.x, rng),
+            f32::random_between(min.y, max.y, rng),
+        )
+    }
+}
+
+impl Lerp for Vec3 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self::lerp(self, other, t)
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        self * factor
+    }
+
+    fn random_between(min: Self, max: Self, rng: &mut impl Rng) -> Self {
+        Self::new(
+            f32::random_between(min.x, max.x, rng),
+            f32::random_between(min.y, max.y, rng),
+            f32::random_between(min.z, max.z, rng),
+        )
+    }
+}
+
+impl Lerp for Vec4 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self::lerp(self, other, t)
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        self * factor
+    }
+
+    fn random_between(min: Self, max: Self, rng: &mut impl Rng) -> Self {
+        Self::new(
+            f32::random_between(min.x, max.x, rng),
+            f32::random_between(min.y, max.y, rng),
+            f32::random_between(min.z, max.z, rng),
+            f32::random_between(min.w, max.w, rng),
+        )
+    }
+}
+
+/// Colors are interpolated and scaled as RGBA vectors: the alpha is interpolated and scaled like
+/// the other channels
+impl Lerp for Color {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        ColorGradient::sample_color(self, other, t)
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        Self::from(Vec4::from(self.as_rgba()) * factor)
+    }
+}
+
+/// Rotations are interpolated with a spherical interpolation, so a random rotation between `min`
+/// and `max` is picked along the slerp path between them, it is not a uniformly distributed
+/// random rotation
+impl Lerp for Quat {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.slerp(other, t)
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        Self::IDENTITY.slerp(self, factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_lerp_rounds_to_the_nearest_value() {
+        assert_eq!(0_usize.lerp(10, 0.0), 0);
+        assert_eq!(0_usize.lerp(10, 0.04), 0);
+        assert_eq!(0_usize.lerp(10, 0.96), 10);
+        assert_eq!(0_usize.lerp(10, 1.0), 10);
+        assert_eq!(3_u32.lerp(4, 0.49), 3);
+        assert_eq!(3_u32.lerp(4, 0.51), 4);
+        assert_eq!((-10_i32).lerp(10, 0.01), -10);
+        assert_eq!((-10_i32).lerp(10, 0.99), 10);
+        assert_eq!(10_i32.lerp(-10, 0.99), -10);
+    }
+
+    #[test]
+    fn integer_scale_rounds_to_the_nearest_value() {
+        assert_eq!(10_usize.scale(0.96), 10);
+        assert_eq!(10_usize.scale(0.04), 0);
+        assert_eq!((-10_i32).scale(0.96), -10);
+        assert_eq!(3_u32.scale(1.5), 5);
+    }
+}
@@ -1,9 +1,19 @@
+pub(crate) mod bvh;
 mod color_gradient;
 mod color_or_gradient;
+mod curve;
+mod lerp;
+pub(crate) mod parallel;
 mod range_or_fixed;
+pub(crate) mod serde_mesh;
+pub(crate) mod serde_transform;
 
+use bevy::prelude::{Mesh, Vec3};
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
 pub use color_gradient::ColorGradient;
 pub use color_or_gradient::ColorOrGradient;
+pub use curve::{Curve, CurveInterpolation, Keyframe};
+pub use lerp::Lerp;
 use rand::Rng;
 pub use range_or_fixed::RangeOrFixed;
 
@@ -34,3 +44,45 @@ pub(crate) fn line_spread(half_extent: f32, thickness: f32, spread_amount: f32)
         -radius_spread(half_extent, thickness, -(spread_amount - 1.0) * 2.0 - 1.0)
     }
 }
+
+/// Retrieves the triangles of a `TriangleList` mesh, using its indices if any. The triangles with
+/// an out of bounds index are skipped.
+///
+/// Returns `None` if the mesh has an other topology or no `Float32x3` positions
+pub(crate) fn mesh_triangles(mesh: &Mesh) -> Option<Vec<[Vec3; 3]>> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let positions =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|positions| match positions {
+                VertexAttributeValues::Float32x3(positions) => Some(positions),
+                _ => None,
+            })?;
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.iter().map(|i| *i as usize).collect(),
+        Some(Indices::U32(indices)) => indices.iter().map(|i| *i as usize).collect(),
+        None => (0..positions.len()).collect(),
+    };
+    let triangles = indices
+        .chunks_exact(3)
+        .filter_map(|triangle| {
+            Some([
+                Vec3::from(*positions.get(triangle[0])?),
+                Vec3::from(*positions.get(triangle[1])?),
+                Vec3::from(*positions.get(triangle[2])?),
+            ])
+        })
+        .collect();
+    Some(triangles)
+}
+
+/// Picks a uniformly distributed random point on `triangle`
+pub(crate) fn random_in_triangle([origin, first, second]: [Vec3; 3], rng: &mut impl Rng) -> Vec3 {
+    let mut ratios = (rng.gen_range(0.0..=1.0), rng.gen_range(0.0..=1.0));
+    if ratios.0 + ratios.1 > 1.0 {
+        // Folds the point back inside the triangle
+        ratios = (1.0 - ratios.0, 1.0 - ratios.1);
+    }
+    origin + (first - origin) * ratios.0 + (second - origin) * ratios.1
+}
@@ -0,0 +1,77 @@
+use crate::particle::ParticleSliceMut;
+use bevy::tasks::TaskPool;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+/// Amount of particles processed by a single task
+pub const PARTICLE_CHUNK_SIZE: usize = 4096;
+
+/// Amount of particle systems processed by a single task
+pub const PARTICLE_SYSTEM_BATCH_SIZE: usize = 8;
+
+/// Applies `f` to every [`PARTICLE_CHUNK_SIZE`] chunk of `particles`, in parallel if there is
+/// more than one chunk
+pub fn for_each_chunk_mut<F>(pool: &TaskPool, particles: ParticleSliceMut<'_>, f: F)
+where
+    F: Fn(ParticleSliceMut<'_>) + Send + Sync,
+{
+    if particles.len() <= PARTICLE_CHUNK_SIZE {
+        f(particles);
+        return;
+    }
+    let f = &f;
+    pool.scope(|scope| {
+        for chunk in particles.into_chunks(PARTICLE_CHUNK_SIZE) {
+            scope.spawn(async move { f(chunk) });
+        }
+    });
+}
+
+/// Applies `f` to every [`PARTICLE_CHUNK_SIZE`] chunk of `particles`, in parallel if there is
+/// more than one chunk, with a random generator per chunk.
+///
+/// The chunk generators are seeded from `rng` so the result only depends on `rng` and on the
+/// amount of particles, not on the task scheduling
+pub fn for_each_rng_chunk_mut<F>(
+    pool: &TaskPool,
+    particles: ParticleSliceMut<'_>,
+    rng: &mut SmallRng,
+    f: F,
+) where
+    F: Fn(&mut SmallRng, ParticleSliceMut<'_>) + Send + Sync,
+{
+    if particles.is_empty() {
+        return;
+    }
+    let seed: u64 = rng.gen();
+    let f = &f;
+    if particles.len() <= PARTICLE_CHUNK_SIZE {
+        f(&mut SmallRng::seed_from_u64(seed), particles);
+        return;
+    }
+    pool.scope(|scope| {
+        for (i, chunk) in particles.into_chunks(PARTICLE_CHUNK_SIZE).enumerate() {
+            let mut chunk_rng = SmallRng::seed_from_u64(seed.wrapping_add(i as u64));
+            scope.spawn(async move { f(&mut chunk_rng, chunk) });
+        }
+    });
+}
+
+/// Maps every [`PARTICLE_CHUNK_SIZE`] chunk of `items` with `f`, in parallel if there is more
+/// than one chunk, and returns the results in the chunk order
+pub fn map_chunks<T, R, F>(pool: &TaskPool, items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send + 'static,
+    F: Fn(&[T]) -> R + Send + Sync,
+{
+    if items.len() <= PARTICLE_CHUNK_SIZE {
+        return vec![f(items)];
+    }
+    let f = &f;
+    pool.scope(|scope| {
+        for chunk in items.chunks(PARTICLE_CHUNK_SIZE) {
+            scope.spawn(async move { f(chunk) });
+        }
+    })
+}
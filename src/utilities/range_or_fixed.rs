@@ -1,13 +1,17 @@
-use crate::Vec3;
-use bevy::prelude::Reflect;
+use crate::{Curve, Lerp};
+use bevy::prelude::{Color, Quat, Reflect, Vec2, Vec3, Vec4};
 use rand::Rng;
-use std::fmt::Debug;
+use serde::{Deserialize, Serialize};
 use std::ops::RangeInclusive;
 
-/// Either a fixed value or a range
-#[derive(Copy, Clone, Debug, Reflect)]
+/// Either a fixed value, a range or curves.
+///
+/// The curve modes are evaluated over the emitter cycle (see
+/// [`ParticleEmitter::cycle_ratio`](crate::ParticleEmitter::cycle_ratio)) when used as a
+/// particle emission parameter, and over the particle lifetime when used in a modifier
+#[derive(Clone, Debug, Reflect, Serialize, Deserialize)]
 #[cfg_attr(feature = "inspector", derive(bevy_inspector_egui::Inspectable))]
-pub enum RangeOrFixed<T: Copy + Clone + Debug + Send + Sync + Reflect + Default> {
+pub enum RangeOrFixed<T: Lerp> {
     /// Fixed value
     Fixed(T),
     /// Linear Range value
@@ -17,7 +21,22 @@ pub enum RangeOrFixed<T: Copy + Clone + Debug + Send + Sync + Reflect + Default>
         /// End Value
         max: T,
     },
-    // TODO: Add more range options
+    /// Value following a curve
+    Curve {
+        /// The curve, evaluated between `0` and `1`
+        curve: Curve,
+        /// Multiplier applied to the curve value
+        multiplier: T,
+    },
+    /// Random value between two curves
+    RandomBetweenTwoCurves {
+        /// Lower curve, evaluated between `0` and `1`
+        min: Curve,
+        /// Upper curve, evaluated between `0` and `1`
+        max: Curve,
+        /// Multiplier applied to the curves value
+        multiplier: T,
+    },
 }
 
 impl Default for RangeOrFixed<f32> {
@@ -26,35 +45,28 @@ impl Default for RangeOrFixed<f32> {
     }
 }
 
-impl From<f32> for RangeOrFixed<f32> {
-    fn from(v: f32) -> Self {
-        Self::Fixed(v)
-    }
+macro_rules! impl_default {
+    ($($t:ty),*) => {
+        $(
+            impl Default for RangeOrFixed<$t> {
+                fn default() -> Self {
+                    Self::Fixed(<$t>::default())
+                }
+            }
+        )*
+    };
 }
 
-impl From<RangeInclusive<f32>> for RangeOrFixed<f32> {
-    fn from(range: RangeInclusive<f32>) -> Self {
-        Self::Range {
-            min: *range.start(),
-            max: *range.end(),
-        }
-    }
-}
+impl_default!(usize, u32, i32, Vec2, Vec3, Vec4, Color, Quat);
 
-impl Default for RangeOrFixed<usize> {
-    fn default() -> Self {
-        Self::Fixed(0)
-    }
-}
-
-impl From<usize> for RangeOrFixed<usize> {
-    fn from(v: usize) -> Self {
+impl<T: Lerp> From<T> for RangeOrFixed<T> {
+    fn from(v: T) -> Self {
         Self::Fixed(v)
     }
 }
 
-impl From<RangeInclusive<usize>> for RangeOrFixed<usize> {
-    fn from(range: RangeInclusive<usize>) -> Self {
+impl<T: Lerp> From<RangeInclusive<T>> for RangeOrFixed<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
         Self::Range {
             min: *range.start(),
             max: *range.end(),
@@ -62,94 +74,137 @@ impl From<RangeInclusive<usize>> for RangeOrFixed<usize> {
     }
 }
 
-impl Default for RangeOrFixed<Vec3> {
-    fn default() -> Self {
-        Self::Fixed(Vec3::ZERO)
+impl From<Curve> for RangeOrFixed<f32> {
+    fn from(curve: Curve) -> Self {
+        Self::Curve {
+            curve,
+            multiplier: 1.0,
+        }
     }
 }
 
-impl From<Vec3> for RangeOrFixed<Vec3> {
-    fn from(v: Vec3) -> Self {
-        Self::Fixed(v)
-    }
+/// Random value between the `min` and `max` curves at `time`
+fn random_between_curves(min: &Curve, max: &Curve, time: f32, rng: &mut impl Rng) -> f32 {
+    let (min, max) = (min.evaluate(time), max.evaluate(time));
+    (max - min).mul_add(rng.gen_range(0.0..=1.0), min)
 }
 
-impl From<RangeInclusive<Vec3>> for RangeOrFixed<Vec3> {
-    fn from(range: RangeInclusive<Vec3>) -> Self {
-        Self::Range {
-            min: *range.start(),
-            max: *range.end(),
-        }
+impl<T: Lerp> RangeOrFixed<T> {
+    /// Evaluates the value using `rng`, the curves being evaluated at `0`
+    #[must_use]
+    pub fn evaluate_rng(&self, rng: &mut impl Rng) -> T {
+        self.evaluate(0.0, rng)
     }
-}
 
-impl RangeOrFixed<f32> {
-    /// Evaluates the float value using `rng`
+    /// Evaluates the value at `time` using `rng`
     #[must_use]
-    pub fn evaluate_rng(&self, rng: &mut impl Rng) -> f32 {
+    pub fn evaluate(&self, time: f32, rng: &mut impl Rng) -> T {
         match self {
             Self::Fixed(v) => *v,
-            Self::Range { min, max } => rng.gen_range(*min..=*max),
+            Self::Range { min, max } => T::random_between(*min, *max, rng),
+            Self::Curve { curve, multiplier } => multiplier.scale(curve.evaluate(time)),
+            Self::RandomBetweenTwoCurves {
+                min,
+                max,
+                multiplier,
+            } => multiplier.scale(random_between_curves(min, max, time, rng)),
         }
     }
 
-    /// Samples the float value
+    /// Samples the value at `delta`, between `0` and `1`. Ranges are interpolated and a random
+    /// value between the two curves is picked using `rng` for [`Self::RandomBetweenTwoCurves`]
     #[must_use]
-    pub fn sample(&self, delta: f32) -> f32 {
+    pub fn sample(&self, delta: f32, rng: &mut impl Rng) -> T {
+        let delta = delta.clamp(0.0, 1.0);
         match self {
             Self::Fixed(v) => *v,
-            Self::Range { min, max } => (max - min).mul_add(delta, *min),
+            Self::Range { min, max } => min.lerp(*max, delta),
+            Self::Curve { curve, multiplier } => multiplier.scale(curve.evaluate(delta)),
+            Self::RandomBetweenTwoCurves {
+                min,
+                max,
+                multiplier,
+            } => multiplier.scale(random_between_curves(min, max, delta, rng)),
         }
     }
 }
 
-impl RangeOrFixed<usize> {
-    /// Evaluates the usize value using `rng`
-    #[must_use]
-    pub fn evaluate_rng(&self, rng: &mut impl Rng) -> usize {
-        match self {
-            Self::Fixed(v) => *v,
-            Self::Range { min, max } => rng.gen_range(*min..=*max),
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    fn rng() -> SmallRng {
+        SmallRng::seed_from_u64(0)
     }
 
-    /// Samples the usize value
-    #[must_use]
-    pub fn sample(&self, delta: f32) -> usize {
-        match self {
-            Self::Fixed(v) => *v,
-            Self::Range { min, max } => ((max - min) as f32).mul_add(delta, *min as f32) as usize,
-        }
+    #[test]
+    fn fixed_values() {
+        let value = RangeOrFixed::Fixed(2.0);
+        let mut rng = rng();
+        assert!((value.evaluate_rng(&mut rng) - 2.0).abs() < f32::EPSILON);
+        assert!((value.evaluate(0.5, &mut rng) - 2.0).abs() < f32::EPSILON);
+        assert!((value.sample(0.5, &mut rng) - 2.0).abs() < f32::EPSILON);
     }
-}
 
-impl RangeOrFixed<Vec3> {
-    /// Evaluates the usize value using `rng`
-    #[must_use]
-    pub fn evaluate_rng(&self, rng: &mut impl Rng) -> Vec3 {
-        match self {
-            Self::Fixed(v) => *v,
-            Self::Range { min, max } => Vec3::new(
-                rng.gen_range(min.x..=max.x),
-                rng.gen_range(min.y..=max.y),
-                rng.gen_range(min.z..=max.z),
-            ),
+    #[test]
+    fn range_values() {
+        let value: RangeOrFixed<f32> = (2.0..=4.0).into();
+        let mut rng = rng();
+        for _ in 0..100 {
+            assert!((2.0..=4.0).contains(&value.evaluate_rng(&mut rng)));
+        }
+        assert!((value.sample(0.0, &mut rng) - 2.0).abs() < f32::EPSILON);
+        assert!((value.sample(0.25, &mut rng) - 2.5).abs() < f32::EPSILON);
+        assert!((value.sample(1.0, &mut rng) - 4.0).abs() < f32::EPSILON);
+        // The sampling delta is clamped
+        assert!((value.sample(-1.0, &mut rng) - 2.0).abs() < f32::EPSILON);
+        assert!((value.sample(2.0, &mut rng) - 4.0).abs() < f32::EPSILON);
+        // Integer ranges include both ends
+        let value: RangeOrFixed<usize> = (0..=2).into();
+        let mut counts = [0; 3];
+        for _ in 0..300 {
+            counts[value.evaluate_rng(&mut rng)] += 1;
         }
+        assert!(counts.iter().all(|count| *count > 0));
+        assert_eq!(value.sample(0.0, &mut rng), 0);
+        assert_eq!(value.sample(0.9, &mut rng), 2);
+        assert_eq!(value.sample(1.0, &mut rng), 2);
     }
 
-    /// Samples the Vec3 Value
-    #[must_use]
-    pub fn sample(&self, delta: f32) -> Vec3 {
-        match self {
-            Self::Fixed(v) => *v,
-            Self::Range { min, max } => {
-                let delta = delta.clamp(0.0, 1.0);
-                Vec3::new(
-                    (max.x - min.x).mul_add(delta, min.x),
-                    (max.y - min.y).mul_add(delta, min.y),
-                    (max.z - min.z).mul_add(delta, min.z),
-                )
-            }
+    #[test]
+    fn curve_values() {
+        let value = RangeOrFixed::Curve {
+            curve: Curve::linear(0.0, 2.0),
+            multiplier: Vec2::new(1.0, 3.0),
+        };
+        let mut rng = rng();
+        assert_eq!(value.evaluate_rng(&mut rng), Vec2::ZERO);
+        assert_eq!(value.evaluate(0.5, &mut rng), Vec2::new(1.0, 3.0));
+        assert_eq!(value.sample(1.0, &mut rng), Vec2::new(2.0, 6.0));
+        assert_eq!(value.sample(2.0, &mut rng), Vec2::new(2.0, 6.0));
+    }
+
+    #[test]
+    fn random_between_two_curves_values() {
+        let value = RangeOrFixed::RandomBetweenTwoCurves {
+            min: Curve::linear(0.0, 1.0),
+            max: Curve::linear(1.0, 3.0),
+            multiplier: 2.0,
+        };
+        let mut rng = rng();
+        for _ in 0..100 {
+            assert!((0.0..=2.0).contains(&value.evaluate_rng(&mut rng)));
+            assert!((2.0..=6.0).contains(&value.evaluate(1.0, &mut rng)));
+            assert!((1.0..=4.0).contains(&value.sample(0.5, &mut rng)));
         }
+        // Identical curves give the curve value
+        let value = RangeOrFixed::RandomBetweenTwoCurves {
+            min: Curve::linear(1.0, 3.0),
+            max: Curve::linear(1.0, 3.0),
+            multiplier: 2.0,
+        };
+        assert!((value.sample(0.5, &mut rng) - 4.0).abs() < f32::EPSILON);
     }
 }
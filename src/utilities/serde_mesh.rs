@@ -0,0 +1,48 @@
+//! Serde proxy for [`Mesh`] which only keeps the vertex positions and indices
+use bevy::prelude::Mesh;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+struct MeshProxy {
+    positions: Vec<[f32; 3]>,
+    #[serde(default)]
+    indices: Option<Vec<u32>>,
+}
+
+pub fn serialize<S: Serializer>(mesh: &Mesh, serializer: S) -> Result<S::Ok, S::Error> {
+    let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions.clone(),
+        Some(_) => {
+            return Err(S::Error::custom(
+                "Expected a mesh with `Float32x3` positions",
+            ))
+        }
+        None => vec![],
+    };
+    let indices = mesh.indices().map(|indices| match indices {
+        Indices::U16(values) => values.iter().map(|i| u32::from(*i)).collect(),
+        Indices::U32(values) => values.clone(),
+    });
+    MeshProxy { positions, indices }.serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Mesh, D::Error> {
+    let proxy = MeshProxy::deserialize(deserializer)?;
+    if let Some(index) = proxy
+        .indices
+        .iter()
+        .flatten()
+        .find(|i| **i as usize >= proxy.positions.len())
+    {
+        return Err(D::Error::custom(format!(
+            "Mesh index {index} is out of bounds"
+        )));
+    }
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, proxy.positions);
+    mesh.set_indices(proxy.indices.map(Indices::U32));
+    Ok(mesh)
+}
@@ -0,0 +1,45 @@
+//! Serde proxy for [`Transform`] which doesn't implement `Serialize` and `Deserialize`
+use bevy::prelude::{Quat, Transform, Vec3};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct TransformProxy {
+    translation: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+}
+
+impl Default for TransformProxy {
+    fn default() -> Self {
+        Transform::identity().into()
+    }
+}
+
+impl From<Transform> for TransformProxy {
+    fn from(transform: Transform) -> Self {
+        Self {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            scale: transform.scale,
+        }
+    }
+}
+
+impl From<TransformProxy> for Transform {
+    fn from(proxy: TransformProxy) -> Self {
+        Self {
+            translation: proxy.translation,
+            rotation: proxy.rotation,
+            scale: proxy.scale,
+        }
+    }
+}
+
+pub fn serialize<S: Serializer>(transform: &Transform, serializer: S) -> Result<S::Ok, S::Error> {
+    TransformProxy::from(*transform).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Transform, D::Error> {
+    TransformProxy::deserialize(deserializer).map(Into::into)
+}